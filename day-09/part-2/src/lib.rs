@@ -0,0 +1,373 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+
+pub type Point = (usize, usize);
+
+// background colors cycled through when rendering basins in the terminal
+const BASIN_COLORS: [u8; 12] = [1, 2, 3, 4, 5, 6, 9, 10, 11, 12, 13, 14];
+
+pub struct HeightMap {
+    pub heights: Vec<Vec<u32>>,
+    pub height: usize,
+    pub width: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Basin {
+    pub low_point: Point,
+    pub size: usize,
+    pub risk_level: u32,
+}
+
+pub struct BasinMap {
+    // basin index for every cell, or None for cells of height 9
+    pub labels: Vec<Vec<Option<usize>>>,
+    pub basins: Vec<Basin>,
+    // cells that can drain into more than one basin, either through lower neighbors in different
+    // basins or across a flat area that leads down into more than one
+    pub multi_drain_cells: Vec<Point>,
+}
+
+impl HeightMap {
+    pub fn new(input: &str) -> Self {
+        let heights: Vec<Vec<u32>> = input
+            .trim()
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .chars()
+                    .map(|c| c.to_digit(10).expect("not a digit"))
+                    .collect()
+            })
+            .collect();
+
+        let height = heights.len();
+        let width = heights[0].len();
+
+        Self {
+            heights,
+            height,
+            width,
+        }
+    }
+
+    pub fn neighbor_coords(&self, (row, col): Point) -> Vec<Point> {
+        let mut neighbor_coords: Vec<Point> = vec![];
+
+        // top
+        if row > 0 {
+            neighbor_coords.push((row - 1, col));
+        }
+        // right
+        if col < self.width - 1 {
+            neighbor_coords.push((row, col + 1));
+        }
+        // bottom
+        if row < self.height - 1 {
+            neighbor_coords.push((row + 1, col));
+        }
+        // left
+        if col > 0 {
+            neighbor_coords.push((row, col - 1));
+        }
+
+        neighbor_coords
+    }
+
+    fn height_at(&self, (row, col): Point) -> u32 {
+        self.heights[row][col]
+    }
+
+    fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height).flat_map(move |row| (0..self.width).map(move |col| (row, col)))
+    }
+
+    pub fn is_low_point(&self, point: Point) -> bool {
+        let height = self.height_at(point);
+        self.neighbor_coords(point)
+            .iter()
+            .all(|neighbor| height < self.height_at(*neighbor))
+    }
+
+    pub fn low_points(&self) -> Vec<Point> {
+        self.points().filter(|p| self.is_low_point(*p)).collect()
+    }
+
+    // the connected area of cells with the same height as the starting point
+    fn flat_area(&self, start: Point) -> Vec<Point> {
+        let height = self.height_at(start);
+        let mut area = vec![start];
+        let mut seen: HashSet<Point> = HashSet::from([start]);
+        let mut i = 0;
+
+        while i < area.len() {
+            for neighbor in self.neighbor_coords(area[i]) {
+                if self.height_at(neighbor) == height && seen.insert(neighbor) {
+                    area.push(neighbor);
+                }
+            }
+            i += 1;
+        }
+
+        area
+    }
+
+    // Every cell that isn't a 9 drains into its lowest neighbor, so visiting the cells from lowest
+    // to highest means that neighbor's basin is already known. Flat areas are handled together:
+    // cells with a lower neighbor drain into it, and the rest of the area joins whichever of
+    // those is nearest. An area with no lower neighbors is the bottom of a new basin.
+    pub fn basin_map(&self) -> BasinMap {
+        let mut cells: Vec<Point> = self
+            .points()
+            .filter(|point| self.height_at(*point) != 9)
+            .collect();
+        cells.sort_by_key(|point| self.height_at(*point));
+
+        let mut basin_ids: Vec<Vec<Option<usize>>> = vec![vec![None; self.width]; self.height];
+        let mut basin_count = 0;
+        let mut multi_drain_cells: Vec<Point> = vec![];
+
+        for start in cells {
+            if basin_ids[start.0][start.1].is_some() {
+                continue;
+            }
+
+            let area = self.flat_area(start);
+            let height = self.height_at(start);
+            let mut drains_into: HashSet<usize> = HashSet::new();
+            let mut queue: VecDeque<Point> = VecDeque::new();
+
+            for cell in &area {
+                let neighbors = self.neighbor_coords(*cell);
+                for neighbor in &neighbors {
+                    if self.height_at(*neighbor) < height {
+                        drains_into.extend(basin_ids[neighbor.0][neighbor.1]);
+                    }
+                }
+
+                let lowest = neighbors
+                    .iter()
+                    .min_by_key(|neighbor| self.height_at(**neighbor))
+                    .expect("neighbors was empty!");
+                if self.height_at(*lowest) < height {
+                    basin_ids[cell.0][cell.1] = basin_ids[lowest.0][lowest.1];
+                    queue.push_back(*cell);
+                }
+            }
+
+            if queue.is_empty() {
+                for cell in &area {
+                    basin_ids[cell.0][cell.1] = Some(basin_count);
+                }
+                basin_count += 1;
+                continue;
+            }
+
+            while let Some(cell) = queue.pop_front() {
+                for neighbor in self.neighbor_coords(cell) {
+                    if self.height_at(neighbor) == height
+                        && basin_ids[neighbor.0][neighbor.1].is_none()
+                    {
+                        basin_ids[neighbor.0][neighbor.1] = basin_ids[cell.0][cell.1];
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            if drains_into.len() > 1 {
+                multi_drain_cells.extend(area);
+            }
+        }
+
+        multi_drain_cells.sort_unstable();
+
+        let mut basin_indexes: HashMap<usize, usize> = HashMap::new();
+        let mut basins: Vec<Basin> = vec![];
+        let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; self.width]; self.height];
+
+        for point in self.points() {
+            let height = self.height_at(point);
+            if height == 9 {
+                continue;
+            }
+
+            let basin_id = basin_ids[point.0][point.1].expect("unlabelled cell!");
+            let basin_index = *basin_indexes.entry(basin_id).or_insert_with(|| {
+                basins.push(Basin {
+                    low_point: point,
+                    size: 0,
+                    risk_level: height + 1,
+                });
+                basins.len() - 1
+            });
+
+            let basin = &mut basins[basin_index];
+            basin.size += 1;
+            if height < self.height_at(basin.low_point) {
+                basin.low_point = point;
+                basin.risk_level = height + 1;
+            }
+
+            labels[point.0][point.1] = Some(basin_index);
+        }
+
+        BasinMap {
+            labels,
+            basins,
+            multi_drain_cells,
+        }
+    }
+}
+
+impl BasinMap {
+    pub fn largest_basin_sizes(&self, n: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.basins.iter().map(|basin| basin.size).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.truncate(n);
+        sizes
+    }
+
+    pub fn total_risk_level(&self) -> u32 {
+        self.basins.iter().map(|basin| basin.risk_level).sum()
+    }
+
+    // renders the height map with each basin given its own background color using ANSI escapes
+    pub fn render(&self, height_map: &HeightMap) -> String {
+        let mut output = String::new();
+
+        for (row, labels) in self.labels.iter().enumerate() {
+            for (col, label) in labels.iter().enumerate() {
+                let height = height_map.heights[row][col];
+                match label {
+                    Some(basin_index) => {
+                        let color = BASIN_COLORS[basin_index % BASIN_COLORS.len()];
+                        write!(output, "\x1b[30;48;5;{}m{}", color, height).unwrap();
+                    }
+                    None => write!(output, "\x1b[0m\x1b[2m{}", height).unwrap(),
+                }
+            }
+            output.push_str("\x1b[0m\n");
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        2199943210
+        3987894921
+        9856789892
+        8767896789
+        9899965678
+    ";
+
+    #[test]
+    fn low_points() {
+        let height_map = HeightMap::new(TEST_INPUT);
+
+        assert_eq!(
+            height_map.low_points(),
+            vec![(0, 1), (0, 9), (2, 2), (4, 6)]
+        );
+    }
+
+    #[test]
+    fn basins() {
+        let height_map = HeightMap::new(TEST_INPUT);
+        let basin_map = height_map.basin_map();
+
+        assert_eq!(
+            basin_map.basins,
+            vec![
+                Basin {
+                    low_point: (0, 1),
+                    size: 3,
+                    risk_level: 2
+                },
+                Basin {
+                    low_point: (0, 9),
+                    size: 9,
+                    risk_level: 1
+                },
+                Basin {
+                    low_point: (2, 2),
+                    size: 14,
+                    risk_level: 6
+                },
+                Basin {
+                    low_point: (4, 6),
+                    size: 9,
+                    risk_level: 6
+                },
+            ]
+        );
+        assert_eq!(basin_map.total_risk_level(), 15);
+        assert_eq!(basin_map.largest_basin_sizes(3), vec![14, 9, 9]);
+        assert!(basin_map.multi_drain_cells.is_empty());
+    }
+
+    #[test]
+    fn every_non_nine_cell_is_labelled() {
+        let height_map = HeightMap::new(TEST_INPUT);
+        let basin_map = height_map.basin_map();
+
+        for row in 0..height_map.height {
+            for col in 0..height_map.width {
+                assert_eq!(
+                    basin_map.labels[row][col].is_some(),
+                    height_map.heights[row][col] != 9
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn multi_drain_cells() {
+        let height_map = HeightMap::new(
+            "
+            9999999
+            9013109
+            9999999
+        ",
+        );
+        let basin_map = height_map.basin_map();
+
+        assert_eq!(basin_map.basins.len(), 2);
+        assert_eq!(basin_map.multi_drain_cells, vec![(1, 3)]);
+    }
+
+    #[test]
+    fn flat_area_between_basins() {
+        let height_map = HeightMap::new(
+            "
+            9999999
+            9011109
+            9999999
+        ",
+        );
+        let basin_map = height_map.basin_map();
+
+        assert_eq!(height_map.low_points(), vec![(1, 1), (1, 5)]);
+        assert_eq!(
+            basin_map.basins,
+            vec![
+                Basin {
+                    low_point: (1, 1),
+                    size: 3,
+                    risk_level: 1
+                },
+                Basin {
+                    low_point: (1, 5),
+                    size: 2,
+                    risk_level: 1
+                },
+            ]
+        );
+        assert_eq!(basin_map.total_risk_level(), 2);
+        assert_eq!(basin_map.multi_drain_cells, vec![(1, 2), (1, 3), (1, 4)]);
+    }
+}
@@ -1,117 +1,41 @@
 // Advent of Code 2021: Day 9, Part 2
 // https://adventofcode.com/2021/day/9
-// Usage `cargo run <input-file>
+// Usage `cargo run <input-file> [--report] [--render]`
+//
+// --report prints the low point, size and risk level of every basin
+// --render prints the height map with each basin highlighted in a different color
 
-use std::{collections::HashSet, env, fs::File, io::BufRead, io::BufReader};
-
-type Point = (usize, usize);
-
-fn neighbor_coords(grid: &Vec<Vec<u32>>, row: usize, col: usize) -> Vec<Point> {
-    let width = grid[0].len();
-    let height = grid.len();
-
-    let mut neighbor_coords: Vec<Point> = vec![];
-
-    // top
-    if row > 0 {
-        neighbor_coords.push((row - 1, col));
-    } // right
-    if col < width - 1 {
-        neighbor_coords.push((row, col + 1));
-    }
-    // bottom
-    if row < height - 1 {
-        neighbor_coords.push((row + 1, col));
-    }
-    // left
-    if col > 0 {
-        neighbor_coords.push((row, col - 1));
-    }
-
-    neighbor_coords
-}
-
-fn lowest_neighbor(grid: &Vec<Vec<u32>>, row: usize, col: usize) -> u32 {
-    let neighbor_coords: Vec<Point> = neighbor_coords(&grid, row, col);
-
-    let neighbors: Vec<u32> = neighbor_coords
-        .iter()
-        .map(|coords| grid[coords.0][coords.1])
-        .collect();
-
-    *neighbors.iter().min().expect("neighbors was empty!")
-}
-
-fn basin_size(grid: &Vec<Vec<u32>>, row: usize, col: usize) -> usize {
-    let mut basin: HashSet<Point> = HashSet::new();
-    let mut coords_checked: HashSet<Point> = HashSet::new();
-    let mut coords_to_check: HashSet<Point> = HashSet::new();
-
-    coords_to_check.insert((row, col));
-
-    loop {
-        let mut new_neighbors: HashSet<Point> = HashSet::new();
-        for coords in &coords_to_check {
-            coords_checked.insert(*coords);
-            for neighbor in neighbor_coords(&grid, coords.0, coords.1)
-                .iter()
-                .filter(|coords| grid[coords.0][coords.1] != 9)
-            {
-                new_neighbors.insert(*neighbor);
-                basin.insert(*neighbor);
-            }
-        }
-        coords_to_check = new_neighbors
-            .difference(&coords_checked)
-            .map(|coords| *coords)
-            .collect();
-        if coords_to_check.is_empty() {
-            break;
-        }
-    }
-
-    basin.len()
-}
+use part_2::HeightMap;
+use std::{env, fs};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let input_file: &String = &args[1];
-
-    let file = File::open(input_file).expect("no such file");
-    let buf = BufReader::new(file);
-    let heights: Vec<Vec<u32>> = buf
-        .lines()
-        .map(|l| l.expect("could not parse line"))
-        .map(|s| {
-            s.chars()
-                .map(|c| c.to_digit(10).expect("not a digit"))
-                .collect()
-        })
-        .collect();
+    let input_file = args.get(1).expect("please supply an input filename");
+    let report = args.iter().any(|arg| arg == "--report");
+    let render = args.iter().any(|arg| arg == "--render");
 
-    let width = heights[0].len();
-    let height = heights.len();
+    let input = fs::read_to_string(input_file).expect("no such file");
+    let height_map = HeightMap::new(&input);
+    let basin_map = height_map.basin_map();
 
-    let mut low_point_coords: Vec<(usize, usize)> = vec![];
+    if render {
+        print!("{}", basin_map.render(&height_map));
+    }
 
-    for row in 0..height {
-        for col in 0..width {
-            if heights[row][col] < lowest_neighbor(&heights, row, col) {
-                low_point_coords.push((row, col));
-            }
+    if report {
+        for basin in &basin_map.basins {
+            println!(
+                "low point: {:?}, size: {}, risk level: {}",
+                basin.low_point, basin.size, basin.risk_level
+            );
         }
+        for cell in &basin_map.multi_drain_cells {
+            println!("cell {:?} drains into more than one basin", cell);
+        }
+        println!("total risk level: {}", basin_map.total_risk_level());
     }
 
-    let mut basin_sizes: Vec<usize> = low_point_coords
-        .iter()
-        .map(|coords| basin_size(&heights, coords.0, coords.1))
-        .collect();
-
-    basin_sizes.sort();
-
-    let num_basins = basin_sizes.len();
-    let biggest_three_basins = &basin_sizes[(num_basins - 3)..num_basins];
-    let product: usize = biggest_three_basins.iter().product();
+    let product: usize = basin_map.largest_basin_sizes(3).iter().product();
 
     println!("product of three largest basin sizes: {}", product);
 }
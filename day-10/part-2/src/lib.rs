@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BracketPair {
+    pub opener: char,
+    pub closer: char,
+    // points for a corrupted line whose first illegal character is this closer
+    pub syntax_error_score: u64,
+    // points for each of these closers needed to complete an incomplete line
+    pub completion_score: u64,
+}
+
+impl BracketPair {
+    pub fn new(opener: char, closer: char, syntax_error_score: u64, completion_score: u64) -> Self {
+        Self {
+            opener,
+            closer,
+            syntax_error_score,
+            completion_score,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LineStatus {
+    Valid,
+    Corrupted {
+        // 1-based position of the first illegal character
+        column: usize,
+        // the closer that would have been legal, or None if no chunk was open
+        expected: Option<char>,
+        found: char,
+    },
+    Incomplete {
+        completion: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Scores {
+    pub syntax_error_score: u64,
+    // None if there were no incomplete lines
+    pub middle_completion_score: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CompletionScoreOverflow {
+    // 1-based number of the line whose completion score doesn't fit in a u64
+    pub line: usize,
+}
+
+impl fmt::Display for CompletionScoreOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the completion score for line {} doesn't fit in a u64",
+            self.line
+        )
+    }
+}
+
+impl Error for CompletionScoreOverflow {}
+
+pub struct ChunkParser {
+    closer_for: HashMap<char, char>,
+    pairs_by_closer: HashMap<char, BracketPair>,
+    // the running completion score is multiplied by this before adding each closer's points
+    completion_multiplier: u64,
+}
+
+impl ChunkParser {
+    pub fn new(pairs: &[BracketPair]) -> Self {
+        let closer_for = pairs
+            .iter()
+            .map(|pair| (pair.opener, pair.closer))
+            .collect();
+        let pairs_by_closer = pairs.iter().map(|pair| (pair.closer, *pair)).collect();
+
+        Self {
+            closer_for,
+            pairs_by_closer,
+            completion_multiplier: 5,
+        }
+    }
+
+    pub fn with_completion_multiplier(mut self, completion_multiplier: u64) -> Self {
+        self.completion_multiplier = completion_multiplier;
+        self
+    }
+
+    pub fn parse_line(&self, line: &str) -> LineStatus {
+        let mut expected_closers: Vec<char> = vec![];
+
+        for (i, c) in line.trim().chars().enumerate() {
+            if let Some(closer) = self.closer_for.get(&c) {
+                expected_closers.push(*closer);
+            } else if expected_closers.last() == Some(&c) {
+                expected_closers.pop();
+            } else {
+                return LineStatus::Corrupted {
+                    column: i + 1,
+                    expected: expected_closers.last().copied(),
+                    found: c,
+                };
+            }
+        }
+
+        if expected_closers.is_empty() {
+            LineStatus::Valid
+        } else {
+            LineStatus::Incomplete {
+                completion: expected_closers.iter().rev().collect(),
+            }
+        }
+    }
+
+    // None if the score doesn't fit in a u64, which long enough completions will overflow
+    pub fn completion_score(&self, completion: &str) -> Option<u64> {
+        completion.chars().try_fold(0u64, |score, c| {
+            score.checked_mul(self.completion_multiplier)?.checked_add(
+                self.pairs_by_closer
+                    .get(&c)
+                    .map_or(0, |p| p.completion_score),
+            )
+        })
+    }
+
+    pub fn syntax_error_score(&self, found: char) -> u64 {
+        self.pairs_by_closer
+            .get(&found)
+            .map_or(0, |pair| pair.syntax_error_score)
+    }
+
+    pub fn scores<'a>(
+        &self,
+        lines: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Scores, CompletionScoreOverflow> {
+        let mut syntax_error_score = 0;
+        let mut completion_scores: Vec<u64> = vec![];
+
+        for (i, line) in lines.into_iter().enumerate() {
+            match self.parse_line(line) {
+                LineStatus::Valid => (),
+                LineStatus::Corrupted { found, .. } => {
+                    syntax_error_score += self.syntax_error_score(found);
+                }
+                LineStatus::Incomplete { completion } => {
+                    let score = self
+                        .completion_score(&completion)
+                        .ok_or(CompletionScoreOverflow { line: i + 1 })?;
+                    completion_scores.push(score);
+                }
+            }
+        }
+
+        completion_scores.sort_unstable();
+
+        Ok(Scores {
+            syntax_error_score,
+            middle_completion_score: completion_scores.get(completion_scores.len() / 2).copied(),
+        })
+    }
+}
+
+impl Default for ChunkParser {
+    fn default() -> Self {
+        Self::new(&[
+            BracketPair::new('(', ')', 3, 1),
+            BracketPair::new('[', ']', 57, 2),
+            BracketPair::new('{', '}', 1197, 3),
+            BracketPair::new('<', '>', 25137, 4),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        [({(<(())[]>[[{[]{<()<>>
+        [(()[<>])]({[<{<<[]>>(
+        {([(<{}[<>[]}>{[]{[(<()>
+        (((({<>}<{<{<>}{[]{[]{}
+        [[<[([]))<([[{}[[()]]]
+        [{[{({}]{}}([{[{{{}}([]
+        {<[[]]>}<{[{[{[]{()[[[]
+        [<(<(<(<{}))><([]([]()
+        <{([([[(<>()){}]>(<<{{
+        <{([{{}}[<[[[<>{}]]]>[]]
+    ";
+
+    #[test]
+    fn valid_line() {
+        let parser = ChunkParser::default();
+
+        assert_eq!(parser.parse_line("{()()()}"), LineStatus::Valid);
+        assert_eq!(parser.parse_line("[<>({}){}[([])<>]]"), LineStatus::Valid);
+    }
+
+    #[test]
+    fn corrupted_line() {
+        let parser = ChunkParser::default();
+
+        assert_eq!(
+            parser.parse_line("{([(<{}[<>[]}>{[]{[(<()>"),
+            LineStatus::Corrupted {
+                column: 13,
+                expected: Some(']'),
+                found: '}'
+            }
+        );
+        assert_eq!(
+            parser.parse_line("())"),
+            LineStatus::Corrupted {
+                column: 3,
+                expected: None,
+                found: ')'
+            }
+        );
+    }
+
+    #[test]
+    fn incomplete_line() {
+        let parser = ChunkParser::default();
+
+        assert_eq!(
+            parser.parse_line("[({(<(())[]>[[{[]{<()<>>"),
+            LineStatus::Incomplete {
+                completion: String::from("}}]])})]")
+            }
+        );
+        assert_eq!(parser.completion_score("}}]])})]"), Some(288957));
+    }
+
+    #[test]
+    fn scores() {
+        let parser = ChunkParser::default();
+
+        assert_eq!(
+            parser.scores(TEST_INPUT.trim().lines()),
+            Ok(Scores {
+                syntax_error_score: 26397,
+                middle_completion_score: Some(288957)
+            })
+        );
+    }
+
+    #[test]
+    fn custom_brackets() {
+        let parser = ChunkParser::new(&[
+            BracketPair::new('(', ')', 1, 1),
+            BracketPair::new('«', '»', 10, 2),
+        ]);
+
+        assert_eq!(
+            parser.parse_line("(«»«"),
+            LineStatus::Incomplete {
+                completion: String::from("»)")
+            }
+        );
+        assert_eq!(
+            parser.scores(["(«)", "(«»«"]),
+            Ok(Scores {
+                syntax_error_score: 1,
+                middle_completion_score: Some(11)
+            })
+        );
+    }
+
+    #[test]
+    fn completion_multiplier() {
+        let parser = ChunkParser::default().with_completion_multiplier(10);
+
+        assert_eq!(parser.completion_score("}}]])})]"), Some(33221312));
+    }
+
+    #[test]
+    fn completion_score_overflow() {
+        let parser = ChunkParser::default();
+        // n closing parentheses score (5^n - 1) / 4, which only fits in a u64 up to n = 28
+        let opened = |count| "(".repeat(count);
+
+        assert_eq!(
+            parser.completion_score(&")".repeat(28)),
+            Some(9313225746154785156)
+        );
+        assert_eq!(parser.completion_score(&")".repeat(29)), None);
+        assert_eq!(
+            parser.scores(["(]", &opened(3), &opened(29)]),
+            Err(CompletionScoreOverflow { line: 3 })
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{Coordinates, OctopusGrid};
+use gif::{Encoder, EncodingError, Frame, Repeat};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write as _;
+use std::io::Write;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const RESET: &str = "\x1b[0m";
+const FLASH: &str = "\x1b[1;30;103m";
+
+// palette index 0 is used for octopi that just flashed, the rest is a dark to light blue ramp
+const PALETTE_SIZE: usize = 16;
+const FLASH_COLOR: [u8; 3] = [255, 255, 200];
+
+// scales an energy level between 1 and the flash threshold into the range 0..=max
+fn scale(energy_level: u32, flash_threshold: u32, max: u32) -> u32 {
+    let energy_level = energy_level.clamp(1, flash_threshold.max(1));
+    (energy_level - 1) * max / flash_threshold.saturating_sub(1).max(1)
+}
+
+// Draws the grid with the octopi that flashed during the last step highlighted. `flashed` is what
+// `OctopusGrid::step_flashes` returned, or nothing for the starting grid.
+pub fn render_frame(grid: &OctopusGrid, flashed: &[Coordinates]) -> String {
+    let flashed: HashSet<&Coordinates> = flashed.iter().collect();
+    let mut output = String::new();
+
+    writeln!(output, "{}step {}", CLEAR_SCREEN, grid.steps_taken).unwrap();
+    for (row, energy_levels) in grid.energy_levels.iter().enumerate() {
+        for (col, energy_level) in energy_levels.iter().enumerate() {
+            if flashed.contains(&(row, col)) {
+                write!(output, "{}{}{}", FLASH, energy_level, RESET).unwrap();
+            } else {
+                // the 256-color grayscale ramp runs from 232 (darkest) to 255 (lightest)
+                let gray = 240 + scale(*energy_level, grid.flash_threshold, 15);
+                write!(output, "\x1b[38;5;{}m{}{}", gray, energy_level, RESET).unwrap();
+            }
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[derive(Debug)]
+pub enum GifError {
+    // gif dimensions are 16-bit
+    TooLarge { width: usize, height: usize },
+    Encoding(EncodingError),
+}
+
+impl fmt::Display for GifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GifError::TooLarge { width, height } => write!(
+                f,
+                "a {}x{} gif is too large, the most either side can be is {}",
+                width,
+                height,
+                u16::MAX
+            ),
+            GifError::Encoding(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for GifError {}
+
+impl From<EncodingError> for GifError {
+    fn from(error: EncodingError) -> Self {
+        GifError::Encoding(error)
+    }
+}
+
+pub struct GifRecorder<W: Write> {
+    encoder: Encoder<W>,
+    // number of pixels along each side of an octopus
+    scale: usize,
+    width: u16,
+    height: u16,
+    // delay between frames, in hundredths of a second
+    delay: u16,
+}
+
+impl<W: Write> GifRecorder<W> {
+    pub fn new(writer: W, grid: &OctopusGrid, scale: usize, delay: u16) -> Result<Self, GifError> {
+        let too_large = || GifError::TooLarge {
+            width: grid.cols.saturating_mul(scale),
+            height: grid.rows.saturating_mul(scale),
+        };
+        let side = |octopi: usize| {
+            octopi
+                .checked_mul(scale)
+                .and_then(|pixels| u16::try_from(pixels).ok())
+        };
+        let width = side(grid.cols).ok_or_else(too_large)?;
+        let height = side(grid.rows).ok_or_else(too_large)?;
+
+        let mut palette: Vec<u8> = FLASH_COLOR.to_vec();
+        for i in 1..PALETTE_SIZE {
+            let brightness = (i * 200 / (PALETTE_SIZE - 1)) as u8;
+            palette.extend([brightness / 4, brightness / 2, brightness]);
+        }
+
+        let mut encoder = Encoder::new(writer, width, height, &palette)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        Ok(Self {
+            encoder,
+            scale,
+            width,
+            height,
+            delay,
+        })
+    }
+
+    // adds a frame of the grid, with `flashed` as for `render_frame`
+    pub fn add_frame(
+        &mut self,
+        grid: &OctopusGrid,
+        flashed: &[Coordinates],
+    ) -> Result<(), EncodingError> {
+        let flashed: HashSet<&Coordinates> = flashed.iter().collect();
+        let mut pixels: Vec<u8> = Vec::with_capacity(self.width as usize * self.height as usize);
+
+        for (row, energy_levels) in grid.energy_levels.iter().enumerate() {
+            let row_pixels: Vec<u8> = energy_levels
+                .iter()
+                .enumerate()
+                .flat_map(|(col, energy_level)| {
+                    let index = if flashed.contains(&(row, col)) {
+                        0
+                    } else {
+                        1 + scale(*energy_level, grid.flash_threshold, PALETTE_SIZE as u32 - 2)
+                    };
+                    vec![index as u8; self.scale]
+                })
+                .collect();
+            for _ in 0..self.scale {
+                pixels.extend(&row_pixels);
+            }
+        }
+
+        let mut frame = Frame::from_indexed_pixels(self.width, self.height, &pixels, None);
+        frame.delay = self.delay;
+        self.encoder.write_frame(&frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_frame_highlights_flashes() {
+        let mut grid = OctopusGrid::new(
+            "
+            19
+            11
+        ",
+        );
+        let flashed = grid.step_flashes();
+
+        let frame = render_frame(&grid, &flashed);
+        let lines: Vec<&str> = frame.lines().collect();
+
+        assert_eq!(lines[0], format!("{}step 1", CLEAR_SCREEN));
+        assert_eq!(
+            lines[1],
+            format!("\x1b[38;5;243m3{}{}0{}", RESET, FLASH, RESET)
+        );
+    }
+
+    #[test]
+    fn starting_zeros_have_not_flashed() {
+        let grid = OctopusGrid::new(
+            "
+            09
+            10
+        ",
+        );
+
+        let frame = render_frame(&grid, &[]);
+        assert!(!frame.contains(FLASH));
+    }
+
+    #[test]
+    fn gif_recorder() {
+        let mut grid = OctopusGrid::new(
+            "
+            19
+            11
+        ",
+        );
+        let mut output: Vec<u8> = vec![];
+
+        {
+            let mut recorder = GifRecorder::new(&mut output, &grid, 4, 10).unwrap();
+            recorder.add_frame(&grid, &[]).unwrap();
+            let flashed = grid.step_flashes();
+            recorder.add_frame(&grid, &flashed).unwrap();
+        }
+
+        assert!(output.starts_with(b"GIF89a"));
+        assert_eq!(&output[6..10], &[8, 0, 8, 0]);
+        assert_eq!(output.last(), Some(&0x3b));
+    }
+
+    #[test]
+    fn gif_too_large() {
+        let grid = OctopusGrid::new(
+            "
+            19
+            11
+        ",
+        );
+
+        assert!(GifRecorder::new(vec![], &grid, 32767, 10).is_ok());
+        assert!(matches!(
+            GifRecorder::new(vec![], &grid, 32768, 10),
+            Err(GifError::TooLarge {
+                width: 65536,
+                height: 65536
+            })
+        ));
+        assert!(matches!(
+            GifRecorder::new(vec![], &grid, usize::MAX, 10),
+            Err(GifError::TooLarge { .. })
+        ));
+    }
+}
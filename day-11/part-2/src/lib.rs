@@ -0,0 +1,293 @@
+pub mod animation;
+
+use std::collections::VecDeque;
+
+pub type Coordinates = (usize, usize);
+
+const DEFAULT_FLASH_THRESHOLD: u32 = 9;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Neighborhood {
+    // the eight surrounding cells, including diagonals
+    Moore,
+    // only the four orthogonally adjacent cells
+    VonNeumann,
+}
+
+impl Neighborhood {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Moore => &[
+                (-1, 0),  // top
+                (-1, 1),  // top right
+                (0, 1),   // right
+                (1, 1),   // bottom right
+                (1, 0),   // bottom
+                (1, -1),  // bottom left
+                (0, -1),  // left
+                (-1, -1), // top left
+            ],
+            Neighborhood::VonNeumann => &[(-1, 0), (0, 1), (1, 0), (0, -1)],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OctopusGrid {
+    pub energy_levels: Vec<Vec<u32>>,
+    pub rows: usize,
+    pub cols: usize,
+    // an octopus flashes once its energy level goes above this
+    pub flash_threshold: u32,
+    pub neighborhood: Neighborhood,
+    pub steps_taken: usize,
+}
+
+impl OctopusGrid {
+    pub fn new(input: &str) -> Self {
+        let energy_levels: Vec<Vec<u32>> = input
+            .trim()
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .chars()
+                    .map(|c| c.to_digit(10).expect("not a digit"))
+                    .collect()
+            })
+            .collect();
+
+        let rows = energy_levels.len();
+        let cols = energy_levels[0].len();
+
+        Self {
+            energy_levels,
+            rows,
+            cols,
+            flash_threshold: DEFAULT_FLASH_THRESHOLD,
+            neighborhood: Neighborhood::Moore,
+            steps_taken: 0,
+        }
+    }
+
+    pub fn with_flash_threshold(mut self, flash_threshold: u32) -> Self {
+        self.flash_threshold = flash_threshold;
+        self
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn adjacent_cells(&self, (row, col): Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.neighborhood
+            .offsets()
+            .iter()
+            .map(move |(d_row, d_col)| (row as isize + d_row, col as isize + d_col))
+            .filter(|(row, col)| {
+                *row >= 0 && *row < self.rows as isize && *col >= 0 && *col < self.cols as isize
+            })
+            .map(|(row, col)| (row as usize, col as usize))
+    }
+
+    // Raises an octopus's energy level, queueing it to flash if that leaves it above the
+    // threshold and it hasn't been queued yet this step. Energy levels that are already above
+    // the threshold, like a starting 9 with a threshold of 4, still flash on the first step.
+    fn energize(
+        &mut self,
+        (row, col): Coordinates,
+        queued: &mut [Vec<bool>],
+        to_flash: &mut VecDeque<Coordinates>,
+    ) {
+        let energy_level = &mut self.energy_levels[row][col];
+        *energy_level = energy_level.saturating_add(1);
+
+        if *energy_level > self.flash_threshold && !queued[row][col] {
+            queued[row][col] = true;
+            to_flash.push_back((row, col));
+        }
+    }
+
+    // Advances the grid by one step and returns the octopi that flashed during it. Each octopus is
+    // queued exactly once, the first time its energy level is above the threshold, so the cascade
+    // never has to rescan the grid.
+    pub fn step_flashes(&mut self) -> Vec<Coordinates> {
+        let mut to_flash: VecDeque<Coordinates> = VecDeque::new();
+        let mut queued = vec![vec![false; self.cols]; self.rows];
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                self.energize((row, col), &mut queued, &mut to_flash);
+            }
+        }
+
+        let mut flashed: Vec<Coordinates> = vec![];
+
+        while let Some(cell) = to_flash.pop_front() {
+            flashed.push(cell);
+            for neighbor in self.adjacent_cells(cell).collect::<Vec<_>>() {
+                self.energize(neighbor, &mut queued, &mut to_flash);
+            }
+        }
+
+        for (row, col) in &flashed {
+            self.energy_levels[*row][*col] = 0;
+        }
+
+        self.steps_taken += 1;
+
+        flashed
+    }
+
+    // advances the grid by one step and returns the number of flashes during that step
+    pub fn step(&mut self) -> usize {
+        self.step_flashes().len()
+    }
+
+    // returns the number of flashes during each of the next `steps` steps
+    pub fn run(&mut self, steps: usize) -> Vec<usize> {
+        (0..steps).map(|_| self.step()).collect()
+    }
+
+    pub fn total_flashes(&mut self, steps: usize) -> usize {
+        self.run(steps).iter().sum()
+    }
+
+    // returns the first step on which every octopus flashed, giving up after `max_steps` more steps
+    pub fn first_synchronized_step(&mut self, max_steps: usize) -> Option<usize> {
+        for _ in 0..max_steps {
+            if self.step() == self.len() {
+                return Some(self.steps_taken);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        5483143223
+        2745854711
+        5264556173
+        6141336146
+        6357385478
+        4167524645
+        2176841721
+        6882881134
+        4846848554
+        5283751526
+    ";
+
+    #[test]
+    fn small_example() {
+        let mut grid = OctopusGrid::new(
+            "
+            11111
+            19991
+            19191
+            19991
+            11111
+        ",
+        );
+
+        assert_eq!(grid.run(2), vec![9, 0]);
+        assert_eq!(
+            grid.energy_levels,
+            OctopusGrid::new(
+                "
+                45654
+                51115
+                61116
+                51115
+                45654
+            "
+            )
+            .energy_levels
+        );
+    }
+
+    #[test]
+    fn total_flashes() {
+        assert_eq!(OctopusGrid::new(TEST_INPUT).total_flashes(10), 204);
+        assert_eq!(OctopusGrid::new(TEST_INPUT).total_flashes(100), 1656);
+    }
+
+    #[test]
+    fn first_synchronized_step() {
+        let mut grid = OctopusGrid::new(TEST_INPUT);
+
+        assert_eq!(grid.first_synchronized_step(1000), Some(195));
+        assert_eq!(grid.energy_levels, vec![vec![0; 10]; 10]);
+    }
+
+    #[test]
+    fn gives_up_without_synchronizing() {
+        let mut grid = OctopusGrid::new(TEST_INPUT);
+
+        assert_eq!(grid.first_synchronized_step(100), None);
+        assert_eq!(grid.steps_taken, 100);
+    }
+
+    #[test]
+    fn custom_threshold_and_neighborhood() {
+        let mut grid = OctopusGrid::new(
+            "
+            000
+            040
+            000
+        ",
+        )
+        .with_flash_threshold(4)
+        .with_neighborhood(Neighborhood::VonNeumann);
+
+        assert_eq!(grid.step(), 1);
+        assert_eq!(
+            grid.energy_levels,
+            vec![vec![1, 2, 1], vec![2, 0, 2], vec![1, 2, 1]]
+        );
+    }
+
+    #[test]
+    fn threshold_below_starting_levels() {
+        let mut grid = OctopusGrid::new(
+            "
+            000
+            090
+            000
+        ",
+        )
+        .with_flash_threshold(4)
+        .with_neighborhood(Neighborhood::VonNeumann);
+
+        // the 9 is already above the threshold, so it flashes straight away
+        assert_eq!(grid.step(), 1);
+        assert_eq!(
+            grid.energy_levels,
+            vec![vec![1, 2, 1], vec![2, 0, 2], vec![1, 2, 1]]
+        );
+
+        // every octopus in the example starts above a threshold of 0
+        let mut grid = OctopusGrid::new(TEST_INPUT).with_flash_threshold(0);
+        assert_eq!(grid.step(), 100);
+        assert_eq!(grid.energy_levels, vec![vec![0; 10]; 10]);
+    }
+
+    #[test]
+    fn unreachable_threshold() {
+        let mut grid = OctopusGrid::new(TEST_INPUT).with_flash_threshold(u32::MAX);
+
+        assert_eq!(grid.run(3), vec![0, 0, 0]);
+        assert_eq!(grid.energy_levels[0][0], 5 + 3);
+    }
+}
@@ -1,112 +1,80 @@
 // Advent of Code 2021: Day 11, Part 2
 // https://adventofcode.com/2021/day/11
-// Usage `cargo run <input-file>
+// Usage `cargo run <input-file> [steps] [--animate] [--gif <output-file>]`
+//
+// steps is the number of steps to count flashes for, 100 by default
+// --animate renders every step in the terminal until the octopi synchronize
+// --gif writes the same frames to an animated gif
 
-use std::{env, fs::File, io::BufRead, io::BufReader};
+use part_2::animation::{render_frame, GifRecorder};
+use part_2::OctopusGrid;
+use std::{env, fs, fs::File, io::BufWriter, process, thread, time::Duration};
 
-type Coordinates = (usize, usize);
+const MAX_STEPS: usize = 100_000;
 
-fn adjacent_cells(energy_levels: &Vec<Vec<u32>>, cell: Coordinates) -> Vec<Coordinates> {
-    let rows = energy_levels[0].len() as isize;
-    let cols = energy_levels.len() as isize;
-
-    let row = cell.0 as isize;
-    let col = cell.1 as isize;
-
-    vec![
-        (row - 1, col),     // top
-        (row - 1, col + 1), // top right
-        (row, col + 1),     // right
-        (row + 1, col + 1), // bottom right
-        (row + 1, col),     // bottom
-        (row + 1, col - 1), // bottom left
-        (row, col - 1),     // left
-        (row - 1, col - 1), // top left
-    ]
-    .iter()
-    .filter(|(row, col)| *row >= 0 && *row < rows && *col >= 0 && *col < cols)
-    .map(|(row, col)| (*row as usize, *col as usize))
-    .collect()
-}
-
-fn handle_flashes(energy_levels: &mut Vec<Vec<u32>>) -> usize {
-    let rows = energy_levels[0].len();
-    let cols = energy_levels.len();
-
-    let mut flashes: usize = 0;
-
-    for row in 0..rows {
-        for col in 0..cols {
-            if energy_levels[row][col] == 10 {
-                flashes += 1;
-                energy_levels[row][col] += 1; // set to 11 to mark that this one flashed
-                let adjacent_cells = adjacent_cells(&energy_levels, (row, col));
-                for (row, col) in adjacent_cells {
-                    if energy_levels[row][col] < 10 {
-                        energy_levels[row][col] += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    flashes
-}
+const FRAME_DELAY_MS: u64 = 100;
+const GIF_SCALE: usize = 16;
 
 fn main() {
-    let input_file: String = env::args()
-        .nth(1)
-        .expect("please supply the input file name");
-
-    let file = File::open(input_file).expect("no such file");
-    let reader = BufReader::new(file);
-
-    let mut energy_levels: Vec<Vec<u32>> = reader
-        .lines()
-        .map(|l| l.expect("failed to parse line"))
-        .map(|s| {
-            s.chars()
-                .map(|char| char.to_digit(10).expect("not a digit"))
-                .collect()
-        })
-        .collect();
-
-    let rows = energy_levels[0].len();
-    let cols = energy_levels.len();
-    let total_octopi = rows * cols;
-    let mut step = 0;
-
-    loop {
-        step += 1;
-
-        let mut step_flashes: usize = 0;
-
-        for row in 0..rows {
-            for col in 0..cols {
-                energy_levels[row][col] += 1;
-            }
+    let mut args = env::args().skip(1);
+    let mut input_file: Option<String> = None;
+    let mut steps: usize = 100;
+    let mut animate = false;
+    let mut gif_file: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--animate" => animate = true,
+            "--gif" => gif_file = Some(args.next().expect("please supply a gif filename")),
+            _ if input_file.is_none() => input_file = Some(arg),
+            _ => steps = arg.parse().expect("the number of steps must be an int"),
         }
+    }
 
+    let input_file = input_file.expect("please supply the input file name");
+    let input = fs::read_to_string(input_file).expect("no such file");
+    let mut grid = OctopusGrid::new(&input);
+
+    if animate || gif_file.is_some() {
+        let mut recorder = gif_file.map(|gif_file| {
+            let file = File::create(gif_file).expect("could not create gif file");
+            GifRecorder::new(
+                BufWriter::new(file),
+                &grid,
+                GIF_SCALE,
+                (FRAME_DELAY_MS / 10) as u16,
+            )
+            .unwrap_or_else(|error| {
+                eprintln!("Could not write the gif: {}", error);
+                process::exit(1);
+            })
+        });
+
+        let mut flashed = vec![];
         loop {
-            let new_flashes = handle_flashes(&mut energy_levels);
-            if new_flashes == 0 {
+            if animate {
+                print!("{}", render_frame(&grid, &flashed));
+                thread::sleep(Duration::from_millis(FRAME_DELAY_MS));
+            }
+            if let Some(recorder) = recorder.as_mut() {
+                recorder
+                    .add_frame(&grid, &flashed)
+                    .expect("could not write gif frame");
+            }
+            if flashed.len() == grid.len() || grid.steps_taken == MAX_STEPS {
                 break;
             }
-            step_flashes += new_flashes;
+            flashed = grid.step_flashes();
         }
 
-        if step_flashes == total_octopi {
-            break;
-        }
-
-        for row in 0..rows {
-            for col in 0..cols {
-                if energy_levels[row][col] > 9 {
-                    energy_levels[row][col] = 0;
-                }
-            }
-        }
+        grid = OctopusGrid::new(&input);
     }
 
-    println!("All the octopi flashed on step {}", step);
+    let total_flashes = grid.clone().total_flashes(steps);
+    println!("Total flashes after {} steps: {}", steps, total_flashes);
+
+    match grid.first_synchronized_step(MAX_STEPS) {
+        Some(step) => println!("All the octopi flashed on step {}", step),
+        None => println!("The octopi never synchronized in {} steps", MAX_STEPS),
+    }
 }
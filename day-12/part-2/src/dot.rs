@@ -0,0 +1,125 @@
+use crate::{CaveId, CaveSystem};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+const SMALL_CAVE_STYLE: &str = "shape=ellipse";
+const BIG_CAVE_STYLE: &str = "shape=box, style=filled, fillcolor=lightgray";
+const HIGHLIGHT_STYLE: &str = "color=red, penwidth=2";
+
+// Renders the cave system as an undirected Graphviz graph, with small caves drawn as ellipses
+// and big caves as filled boxes. The caves and connections along `path` are drawn in red, so an
+// empty path highlights nothing.
+pub fn to_dot(cave_system: &CaveSystem, path: &[String]) -> String {
+    let path: Vec<CaveId> = path
+        .iter()
+        .map(|name| {
+            cave_system
+                .id(name)
+                .unwrap_or_else(|| panic!("no such cave in path: {}", name))
+        })
+        .collect();
+
+    let path_caves: HashSet<CaveId> = path.iter().copied().collect();
+    let path_connections: HashSet<(CaveId, CaveId)> = path
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(
+                cave_system.adjacent_caves[a].contains(&b),
+                "caves in path are not connected: {}-{}",
+                cave_system.names[a],
+                cave_system.names[b]
+            );
+            (a.min(b), a.max(b))
+        })
+        .collect();
+
+    let mut dot = String::from("graph caves {\n");
+
+    for (cave, name) in cave_system.names.iter().enumerate() {
+        let style = if cave_system.is_small(cave) {
+            SMALL_CAVE_STYLE
+        } else {
+            BIG_CAVE_STYLE
+        };
+        if path_caves.contains(&cave) {
+            writeln!(dot, "    \"{}\" [{}, {}];", name, style, HIGHLIGHT_STYLE).unwrap();
+        } else {
+            writeln!(dot, "    \"{}\" [{}];", name, style).unwrap();
+        }
+    }
+
+    for (a, adjacent_caves) in cave_system.adjacent_caves.iter().enumerate() {
+        // every connection is stored in both directions, so only write it out from the lower id
+        for b in adjacent_caves.iter().filter(|b| a < **b) {
+            let (a_name, b_name) = (&cave_system.names[a], &cave_system.names[*b]);
+            if path_connections.contains(&(a, *b)) {
+                writeln!(
+                    dot,
+                    "    \"{}\" -- \"{}\" [{}];",
+                    a_name, b_name, HIGHLIGHT_STYLE
+                )
+                .unwrap();
+            } else {
+                writeln!(dot, "    \"{}\" -- \"{}\";", a_name, b_name).unwrap();
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        start-A
+        start-b
+        A-b
+        A-end
+    ";
+
+    #[test]
+    fn cave_graph() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+
+        assert_eq!(
+            to_dot(&cave_system, &[]),
+            "graph caves {
+    \"start\" [shape=ellipse];
+    \"A\" [shape=box, style=filled, fillcolor=lightgray];
+    \"b\" [shape=ellipse];
+    \"end\" [shape=ellipse];
+    \"start\" -- \"A\";
+    \"start\" -- \"b\";
+    \"A\" -- \"b\";
+    \"A\" -- \"end\";
+}
+"
+        );
+    }
+
+    #[test]
+    fn highlighted_path() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+        let path: Vec<String> = vec!["start".into(), "b".into(), "A".into(), "end".into()];
+        let dot = to_dot(&cave_system, &path);
+
+        assert!(dot.contains("\"b\" [shape=ellipse, color=red, penwidth=2];"));
+        assert!(dot.contains("\"start\" -- \"b\" [color=red, penwidth=2];"));
+        assert!(dot.contains("\"A\" -- \"b\" [color=red, penwidth=2];"));
+        assert!(dot.contains("\"start\" -- \"A\";\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "not connected: b-end")]
+    fn rejects_disconnected_path() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+        let path: Vec<String> = vec!["start".into(), "b".into(), "end".into()];
+
+        to_dot(&cave_system, &path);
+    }
+}
@@ -0,0 +1,450 @@
+pub mod dot;
+
+use std::collections::{HashMap, HashSet};
+
+pub type CaveId = usize;
+
+pub struct CaveSystem {
+    pub names: Vec<String>,
+    pub adjacent_caves: Vec<Vec<CaveId>>,
+    ids: HashMap<String, CaveId>,
+    // the position of each small cave in the packed visit counts, None for big caves
+    small_cave_indexes: Vec<Option<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisitPolicy {
+    pub start: String,
+    pub end: String,
+    // how many times each small cave may be visited
+    pub max_visits: usize,
+    // how many small caves may be visited more than max_visits times
+    pub exceptions: usize,
+    // how many times each of those exceptional caves may be visited
+    pub exception_max_visits: usize,
+    // caves which may not be entered at all
+    pub forbidden_caves: HashSet<String>,
+}
+
+impl VisitPolicy {
+    // small caves at most once
+    pub fn part_1() -> Self {
+        Self {
+            start: String::from("start"),
+            end: String::from("end"),
+            max_visits: 1,
+            exceptions: 0,
+            exception_max_visits: 1,
+            forbidden_caves: HashSet::new(),
+        }
+    }
+
+    // small caves at most once, except for a single small cave which may be visited twice
+    pub fn part_2() -> Self {
+        Self::part_1().with_exceptions(1, 2)
+    }
+
+    pub fn with_start_and_end(mut self, start: &str, end: &str) -> Self {
+        self.start = start.to_string();
+        self.end = end.to_string();
+        self
+    }
+
+    pub fn with_max_visits(mut self, max_visits: usize) -> Self {
+        self.max_visits = max_visits;
+        self
+    }
+
+    pub fn with_exceptions(mut self, exceptions: usize, exception_max_visits: usize) -> Self {
+        self.exceptions = exceptions;
+        self.exception_max_visits = exception_max_visits;
+        self
+    }
+
+    pub fn with_forbidden_cave(mut self, cave: &str) -> Self {
+        self.forbidden_caves.insert(cave.to_string());
+        self
+    }
+}
+
+fn is_small(cave: &str) -> bool {
+    cave.to_lowercase() == cave
+}
+
+impl CaveSystem {
+    pub fn new(input: &str) -> Self {
+        let mut cave_system = Self {
+            names: vec![],
+            adjacent_caves: vec![],
+            ids: HashMap::new(),
+            small_cave_indexes: vec![],
+        };
+
+        for line in input.trim().lines() {
+            let (a, b) = line
+                .trim()
+                .split_once('-')
+                .expect("connections should look like a-b");
+            let a = cave_system.intern(a);
+            let b = cave_system.intern(b);
+
+            if !cave_system.is_small(a) && !cave_system.is_small(b) {
+                panic!(
+                    "connecting two big caves ({}-{}) would allow infinitely many paths",
+                    cave_system.names[a], cave_system.names[b]
+                );
+            }
+
+            cave_system.adjacent_caves[a].push(b);
+            cave_system.adjacent_caves[b].push(a);
+        }
+
+        cave_system
+    }
+
+    fn intern(&mut self, name: &str) -> CaveId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = self.names.len();
+        let small_cave_index = if is_small(name) {
+            Some(self.small_cave_indexes.iter().flatten().count())
+        } else {
+            None
+        };
+
+        self.names.push(name.to_string());
+        self.adjacent_caves.push(vec![]);
+        self.ids.insert(name.to_string(), id);
+        self.small_cave_indexes.push(small_cave_index);
+
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<CaveId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn is_small(&self, cave: CaveId) -> bool {
+        self.small_cave_indexes[cave].is_some()
+    }
+
+    pub fn count_paths(&self, policy: &VisitPolicy) -> usize {
+        let traversal = Traversal::new(self, policy);
+        let mut memo: HashMap<(CaveId, Visits), usize> = HashMap::new();
+
+        traversal.count_paths_from(traversal.start, traversal.initial_visits(), &mut memo)
+    }
+
+    // lists every path allowed by the policy, in lexical order of the cave names along each path
+    pub fn list_paths(&self, policy: &VisitPolicy) -> Vec<Vec<String>> {
+        let traversal = Traversal::new(self, policy);
+        let mut paths: Vec<Vec<String>> = vec![];
+        let mut path: Vec<CaveId> = vec![traversal.start];
+
+        traversal.list_paths_from(&mut path, traversal.initial_visits(), &mut paths);
+
+        paths
+    }
+}
+
+// the packed visit counts of every small cave along with the number of policy exceptions used
+type Visits = (u128, usize);
+
+struct Traversal<'a> {
+    cave_system: &'a CaveSystem,
+    policy: &'a VisitPolicy,
+    start: CaveId,
+    end: CaveId,
+    forbidden: Vec<bool>,
+    // neighbors of each cave ordered by name, so paths are found in lexical order
+    sorted_adjacent_caves: Vec<Vec<CaveId>>,
+    bits_per_cave: usize,
+}
+
+impl<'a> Traversal<'a> {
+    fn new(cave_system: &'a CaveSystem, policy: &'a VisitPolicy) -> Self {
+        let start = cave_system.id(&policy.start).expect("no such start cave");
+        let end = cave_system.id(&policy.end).expect("no such end cave");
+
+        let forbidden = cave_system
+            .names
+            .iter()
+            .map(|name| policy.forbidden_caves.contains(name))
+            .collect();
+
+        let sorted_adjacent_caves = cave_system
+            .adjacent_caves
+            .iter()
+            .map(|caves| {
+                let mut caves = caves.clone();
+                caves.sort_by_key(|cave| &cave_system.names[*cave]);
+                caves
+            })
+            .collect();
+
+        let most_visits = policy.max_visits.max(policy.exception_max_visits);
+        let bits_per_cave = (usize::BITS - most_visits.leading_zeros()).max(1) as usize;
+        let small_caves = cave_system.small_cave_indexes.iter().flatten().count();
+        assert!(
+            small_caves * bits_per_cave <= u128::BITS as usize,
+            "too many small caves to track {} visits to each",
+            most_visits
+        );
+
+        Self {
+            cave_system,
+            policy,
+            start,
+            end,
+            forbidden,
+            sorted_adjacent_caves,
+            bits_per_cave,
+        }
+    }
+
+    fn initial_visits(&self) -> Visits {
+        (self.record_visit(0, self.start), 0)
+    }
+
+    fn visit_count(&self, visit_counts: u128, cave: CaveId) -> usize {
+        match self.cave_system.small_cave_indexes[cave] {
+            Some(index) => {
+                let mask = (1 << self.bits_per_cave) - 1;
+                ((visit_counts >> (index * self.bits_per_cave)) & mask) as usize
+            }
+            None => 0,
+        }
+    }
+
+    fn record_visit(&self, visit_counts: u128, cave: CaveId) -> u128 {
+        match self.cave_system.small_cave_indexes[cave] {
+            Some(index) => visit_counts + (1 << (index * self.bits_per_cave)),
+            None => visit_counts,
+        }
+    }
+
+    // returns the visits after moving into the cave, or None if the policy doesn't allow it
+    fn visit(&self, (visit_counts, exceptions_used): Visits, cave: CaveId) -> Option<Visits> {
+        if cave == self.start || self.forbidden[cave] {
+            return None;
+        }
+        if !self.cave_system.is_small(cave) {
+            return Some((visit_counts, exceptions_used));
+        }
+
+        let visits = self.visit_count(visit_counts, cave) + 1;
+        let visit_counts = self.record_visit(visit_counts, cave);
+
+        if visits <= self.policy.max_visits {
+            Some((visit_counts, exceptions_used))
+        } else if visits > self.policy.exception_max_visits {
+            None
+        } else if visits > self.policy.max_visits + 1 {
+            // this cave already used up an exception
+            Some((visit_counts, exceptions_used))
+        } else if exceptions_used < self.policy.exceptions {
+            Some((visit_counts, exceptions_used + 1))
+        } else {
+            None
+        }
+    }
+
+    fn count_paths_from(
+        &self,
+        cave: CaveId,
+        visits: Visits,
+        memo: &mut HashMap<(CaveId, Visits), usize>,
+    ) -> usize {
+        if cave == self.end {
+            return 1;
+        }
+        if let Some(count) = memo.get(&(cave, visits)) {
+            return *count;
+        }
+
+        let count = self.cave_system.adjacent_caves[cave]
+            .iter()
+            .filter_map(|next_cave| {
+                self.visit(visits, *next_cave)
+                    .map(|visits| self.count_paths_from(*next_cave, visits, memo))
+            })
+            .sum();
+
+        memo.insert((cave, visits), count);
+
+        count
+    }
+
+    fn list_paths_from(
+        &self,
+        path: &mut Vec<CaveId>,
+        visits: Visits,
+        paths: &mut Vec<Vec<String>>,
+    ) {
+        let cave = *path.last().unwrap();
+
+        if cave == self.end {
+            paths.push(
+                path.iter()
+                    .map(|cave| self.cave_system.names[*cave].clone())
+                    .collect(),
+            );
+            return;
+        }
+
+        for next_cave in &self.sorted_adjacent_caves[cave] {
+            if let Some(visits) = self.visit(visits, *next_cave) {
+                path.push(*next_cave);
+                self.list_paths_from(path, visits, paths);
+                path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        start-A
+        start-b
+        A-c
+        A-b
+        b-d
+        A-end
+        b-end
+    ";
+
+    #[test]
+    fn interns_caves() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+
+        assert_eq!(cave_system.names, vec!["start", "A", "b", "c", "d", "end"]);
+        assert_eq!(cave_system.id("c"), Some(3));
+        assert!(!cave_system.is_small(1));
+        assert_eq!(cave_system.small_cave_indexes[3], Some(2));
+        assert_eq!(cave_system.adjacent_caves[1], vec![0, 3, 2, 5]);
+    }
+
+    #[test]
+    fn count_paths_without_revisits() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+
+        assert_eq!(cave_system.count_paths(&VisitPolicy::part_1()), 10);
+    }
+
+    #[test]
+    fn count_paths_with_one_revisit() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+
+        assert_eq!(cave_system.count_paths(&VisitPolicy::part_2()), 36);
+    }
+
+    #[test]
+    fn larger_examples() {
+        let slightly_larger = CaveSystem::new(include_str!("../test-2.txt"));
+        let even_larger = CaveSystem::new(include_str!("../test-3.txt"));
+
+        assert_eq!(slightly_larger.count_paths(&VisitPolicy::part_1()), 19);
+        assert_eq!(slightly_larger.count_paths(&VisitPolicy::part_2()), 103);
+        assert_eq!(even_larger.count_paths(&VisitPolicy::part_1()), 226);
+        assert_eq!(even_larger.count_paths(&VisitPolicy::part_2()), 3509);
+    }
+
+    #[test]
+    fn list_paths() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+        let paths: Vec<String> = cave_system
+            .list_paths(&VisitPolicy::part_1())
+            .iter()
+            .map(|path| path.join(","))
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "start,A,b,A,c,A,end",
+                "start,A,b,A,end",
+                "start,A,b,end",
+                "start,A,c,A,b,A,end",
+                "start,A,c,A,b,end",
+                "start,A,c,A,end",
+                "start,A,end",
+                "start,b,A,c,A,end",
+                "start,b,A,end",
+                "start,b,end",
+            ]
+        );
+    }
+
+    #[test]
+    fn list_paths_matches_count() {
+        let cave_system = CaveSystem::new(include_str!("../test-2.txt"));
+        let policy = VisitPolicy::part_2();
+
+        assert_eq!(
+            cave_system.list_paths(&policy).len(),
+            cave_system.count_paths(&policy)
+        );
+    }
+
+    #[test]
+    fn more_visits_and_exceptions() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+        let policy = VisitPolicy::part_1().with_exceptions(2, 2);
+        let paths = cave_system.list_paths(&policy);
+
+        assert_eq!(cave_system.count_paths(&policy), paths.len());
+        assert!(paths.contains(
+            &"start,A,b,A,b,A,c,A,c,A,end"
+                .split(',')
+                .map(String::from)
+                .collect()
+        ));
+
+        let policy = VisitPolicy::part_1()
+            .with_max_visits(2)
+            .with_exceptions(1, 3);
+        assert!(cave_system.count_paths(&policy) > cave_system.count_paths(&VisitPolicy::part_2()));
+    }
+
+    #[test]
+    fn forbidden_caves() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+        let policy = VisitPolicy::part_1().with_forbidden_cave("b");
+
+        assert_eq!(
+            cave_system.list_paths(&policy),
+            vec![
+                vec!["start", "A", "c", "A", "end"],
+                vec!["start", "A", "end"]
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_start_and_end() {
+        let cave_system = CaveSystem::new(TEST_INPUT);
+        let policy = VisitPolicy::part_1()
+            .with_start_and_end("d", "c")
+            .with_forbidden_cave("end");
+
+        assert_eq!(
+            cave_system.list_paths(&policy),
+            vec![
+                vec!["d", "b", "A", "c"],
+                vec!["d", "b", "A", "start", "A", "c"],
+                vec!["d", "b", "start", "A", "c"],
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "infinitely many paths")]
+    fn rejects_connected_big_caves() {
+        CaveSystem::new("start-A\nA-B\nB-end");
+    }
+}
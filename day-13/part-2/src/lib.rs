@@ -0,0 +1,387 @@
+pub mod ocr;
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+pub type Dot = (usize, usize);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Fold {
+    pub axis: Axis,
+    pub line: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    InvalidDot {
+        line: usize,
+        text: String,
+    },
+    InvalidFold {
+        line: usize,
+        text: String,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidDot { line, text } => {
+                write!(f, "line {}: invalid dot {:?}, expected x,y", line, text)
+            }
+            ParseError::InvalidFold { line, text, reason } => {
+                write!(f, "line {}: invalid fold {:?}, {}", line, text, reason)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, PartialEq)]
+pub enum FoldError {
+    DotOnFoldLine { dot: Dot, fold: Fold },
+}
+
+impl fmt::Display for FoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FoldError::DotOnFoldLine { dot, fold } => {
+                write!(f, "dot {},{} lies on the fold line {}", dot.0, dot.1, fold)
+            }
+        }
+    }
+}
+
+impl Error for FoldError {}
+
+impl fmt::Display for Fold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let axis = match self.axis {
+            Axis::X => 'x',
+            Axis::Y => 'y',
+        };
+        write!(f, "{}={}", axis, self.line)
+    }
+}
+
+impl Fold {
+    fn parse(line: usize, text: &str) -> Result<Self, ParseError> {
+        let invalid = |reason| ParseError::InvalidFold {
+            line,
+            text: text.to_string(),
+            reason,
+        };
+
+        let instruction = text
+            .strip_prefix("fold along ")
+            .ok_or_else(|| invalid("expected it to start with \"fold along \""))?;
+        let (axis, fold_line) = instruction
+            .split_once('=')
+            .ok_or_else(|| invalid("expected axis=line"))?;
+        let axis = match axis {
+            "x" => Axis::X,
+            "y" => Axis::Y,
+            _ => return Err(invalid("the axis must be x or y")),
+        };
+        let fold_line = fold_line
+            .parse()
+            .map_err(|_| invalid("the line must be a non-negative integer"))?;
+
+        Ok(Self {
+            axis,
+            line: fold_line,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Paper {
+    pub dots: HashSet<Dot>,
+    width: usize,
+    height: usize,
+}
+
+impl Paper {
+    // the paper is assumed to be just big enough to hold all of the dots
+    pub fn new(dots: impl IntoIterator<Item = Dot>) -> Self {
+        let dots: HashSet<Dot> = dots.into_iter().collect();
+        let width = dots.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+        let height = dots.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+
+        Self {
+            dots,
+            width,
+            height,
+        }
+    }
+
+    // reads the dots and fold instructions from the puzzle input
+    pub fn parse(input: &str) -> Result<(Self, Vec<Fold>), ParseError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, text)| (i + 1, text.trim()))
+            .skip_while(|(_, text)| text.is_empty());
+
+        let mut dots: Vec<Dot> = vec![];
+        for (line, text) in lines.by_ref() {
+            if text.is_empty() {
+                break;
+            }
+            let dot = text
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .ok_or_else(|| ParseError::InvalidDot {
+                    line,
+                    text: text.to_string(),
+                })?;
+            dots.push(dot);
+        }
+
+        let folds = lines
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(line, text)| Fold::parse(line, text))
+            .collect::<Result<Vec<Fold>, ParseError>>()?;
+
+        Ok((Self::new(dots), folds))
+    }
+
+    // Folds the far side of the paper over onto the near side and returns the number of dots
+    // still visible. If the far side is the bigger of the two it overhangs the near side, so
+    // everything is shifted over to keep the coordinates of the folded paper from going negative.
+    // The paper is only known to be as big as its dots, so a line past them has nothing on its far
+    // side and folding just trims the paper down to the line.
+    pub fn fold(&mut self, fold: &Fold) -> Result<usize, FoldError> {
+        let line = fold.line;
+        let size = match fold.axis {
+            Axis::X => self.width,
+            Axis::Y => self.height,
+        };
+
+        if line >= size {
+            match fold.axis {
+                Axis::X => self.width = line,
+                Axis::Y => self.height = line,
+            }
+            return Ok(self.dots.len());
+        }
+
+        let folded_size = line.max(size - 1 - line);
+        let offset = folded_size - line;
+
+        let fold_coordinate = |coordinate: usize| {
+            if coordinate < line {
+                coordinate + offset
+            } else {
+                offset + line - (coordinate - line)
+            }
+        };
+
+        let mut dots: HashSet<Dot> = HashSet::new();
+        for &(x, y) in &self.dots {
+            let coordinate = match fold.axis {
+                Axis::X => x,
+                Axis::Y => y,
+            };
+            if coordinate == line {
+                return Err(FoldError::DotOnFoldLine {
+                    dot: (x, y),
+                    fold: *fold,
+                });
+            }
+            dots.insert(match fold.axis {
+                Axis::X => (fold_coordinate(x), y),
+                Axis::Y => (x, fold_coordinate(y)),
+            });
+        }
+
+        self.dots = dots;
+        match fold.axis {
+            Axis::X => self.width = folded_size,
+            Axis::Y => self.height = folded_size,
+        }
+
+        Ok(self.dots.len())
+    }
+
+    // applies every fold in turn, returning the number of dots visible after each one
+    pub fn fold_all(&mut self, folds: &[Fold]) -> Result<Vec<usize>, FoldError> {
+        folds.iter().map(|fold| self.fold(fold)).collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let display = if self.dots.contains(&(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                };
+                output.push(display);
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        6,10
+        0,14
+        9,10
+        0,3
+        10,4
+        4,11
+        6,0
+        6,12
+        4,1
+        0,13
+        10,12
+        3,4
+        3,0
+        8,4
+        1,10
+        2,14
+        8,10
+        9,0
+
+        fold along y=7
+        fold along x=5
+    ";
+
+    #[test]
+    fn parse() {
+        let (paper, folds) = Paper::parse(TEST_INPUT).unwrap();
+
+        assert_eq!(paper.dots.len(), 18);
+        assert!(paper.dots.contains(&(6, 10)));
+        assert_eq!(
+            folds,
+            vec![
+                Fold {
+                    axis: Axis::Y,
+                    line: 7
+                },
+                Fold {
+                    axis: Axis::X,
+                    line: 5
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn fold_all() {
+        let (mut paper, folds) = Paper::parse(TEST_INPUT).unwrap();
+
+        assert_eq!(paper.fold_all(&folds), Ok(vec![17, 16]));
+        assert_eq!(
+            paper.render(),
+            "#####\n\
+             #...#\n\
+             #...#\n\
+             #...#\n\
+             #####\n\
+             .....\n\
+             .....\n"
+        );
+    }
+
+    #[test]
+    fn asymmetric_fold() {
+        let mut paper = Paper::new([(0, 0), (1, 0), (3, 0), (6, 1)]);
+        let fold = Fold {
+            axis: Axis::X,
+            line: 2,
+        };
+
+        // the right side is four columns wide, so it overhangs the two columns on the left
+        assert_eq!(paper.fold(&fold), Ok(3));
+        assert_eq!(paper.width(), 4);
+        assert_eq!(paper.render(), "..##\n#...\n");
+    }
+
+    #[test]
+    fn dot_on_fold_line() {
+        let mut paper = Paper::new([(0, 0), (1, 2), (0, 4)]);
+        let fold = Fold {
+            axis: Axis::Y,
+            line: 2,
+        };
+
+        assert_eq!(
+            paper.fold(&fold),
+            Err(FoldError::DotOnFoldLine { dot: (1, 2), fold })
+        );
+        assert_eq!(
+            FoldError::DotOnFoldLine { dot: (1, 2), fold }.to_string(),
+            "dot 1,2 lies on the fold line y=2"
+        );
+    }
+
+    #[test]
+    fn fold_past_the_dots() {
+        let mut paper = Paper::new([(0, 0), (1, 0)]);
+        let fold = Fold {
+            axis: Axis::X,
+            line: 5,
+        };
+
+        assert_eq!(paper.fold(&fold), Ok(2));
+        assert_eq!(paper.width(), 5);
+        assert_eq!(paper.render(), "##...\n");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Paper::parse("1,2\n3;4\n\nfold along x=1"),
+            Err(ParseError::InvalidDot {
+                line: 2,
+                text: String::from("3;4")
+            })
+        );
+
+        let error = Paper::parse("1,2\n\nfold along x=1\nfold across y=1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4: invalid fold \"fold across y=1\", expected it to start with \"fold along \""
+        );
+
+        let error = Paper::parse("1,2\n\nfold along z=1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: invalid fold \"fold along z=1\", the axis must be x or y"
+        );
+
+        let error = Paper::parse("1,2\n\nfold along y=-1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: invalid fold \"fold along y=-1\", the line must be a non-negative integer"
+        );
+    }
+}
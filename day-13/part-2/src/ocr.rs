@@ -0,0 +1,117 @@
+use crate::Paper;
+
+pub const LETTER_WIDTH: usize = 4;
+pub const LETTER_HEIGHT: usize = 6;
+
+// letters are separated by a single empty column
+const LETTER_SPACING: usize = LETTER_WIDTH + 1;
+
+// the capital letters used by the puzzle, with their rows joined together
+const FONT: [(char, &str); 16] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+fn glyph(paper: &Paper, index: usize) -> String {
+    let left = index * LETTER_SPACING;
+
+    (0..LETTER_HEIGHT)
+        .flat_map(|y| (left..left + LETTER_WIDTH).map(move |x| (x, y)))
+        .map(|dot| if paper.dots.contains(&dot) { '#' } else { '.' })
+        .collect()
+}
+
+// Reads the code left on the paper after folding, or None if the dots don't spell out letters
+// in the puzzle's font.
+pub fn read_code(paper: &Paper) -> Option<String> {
+    if paper.dots.is_empty() || paper.height() > LETTER_HEIGHT {
+        return None;
+    }
+
+    let letters = paper.width().div_ceil(LETTER_SPACING);
+
+    (0..letters)
+        .map(|index| {
+            let glyph = glyph(paper, index);
+            FONT.iter()
+                .find(|(_, font_glyph)| *font_glyph == glyph)
+                .map(|(letter, _)| *letter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper_from(rendered: &str) -> Paper {
+        Paper::new(rendered.trim().lines().enumerate().flat_map(|(y, row)| {
+            row.trim()
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| (x, y))
+        }))
+    }
+
+    #[test]
+    fn reads_code() {
+        let paper = paper_from(
+            "
+            .##...##..####...##.#..#.####..##..#..#
+            #..#.#..#.#.......#.#..#....#.#..#.#.#.
+            #....#..#.###.....#.####...#..#....##..
+            #....####.#.......#.#..#..#...#....#.#.
+            #..#.#..#.#....#..#.#..#.#....#..#.#.#.
+            .##..#..#.#.....##..#..#.####..##..#..#
+        ",
+        );
+
+        assert_eq!(read_code(&paper), Some(String::from("CAFJHZCK")));
+    }
+
+    #[test]
+    fn every_letter_round_trips() {
+        for (letter, font_glyph) in FONT {
+            let paper = paper_from(
+                &font_glyph
+                    .as_bytes()
+                    .chunks(LETTER_WIDTH)
+                    .map(|row| String::from_utf8(row.to_vec()).unwrap())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            );
+
+            assert_eq!(read_code(&paper), Some(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        let paper = paper_from(
+            "
+            #####
+            #...#
+            #...#
+            #...#
+            #####
+        ",
+        );
+
+        assert_eq!(read_code(&paper), None);
+    }
+}
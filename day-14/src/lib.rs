@@ -0,0 +1,288 @@
+pub mod recurrence;
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+pub type Histogram = BTreeMap<char, u128>;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CountOverflow {
+    pub step: usize,
+}
+
+impl fmt::Display for CountOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "element counts no longer fit in a u128 on step {}",
+            self.step
+        )
+    }
+}
+
+impl Error for CountOverflow {}
+
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    // element ids index into this
+    pub elements: Vec<char>,
+    // how many times each pair occurs, indexed by first * elements.len() + second
+    pair_counts: Vec<u128>,
+    // the element inserted between each pair, if there's a rule for it
+    insertions: Vec<Option<usize>>,
+    // the element ids of the polymer template
+    template: Vec<usize>,
+    // the last element never changes and is the only one that isn't the first of some pair
+    last_element: usize,
+    pub steps_taken: usize,
+}
+
+impl Polymer {
+    pub fn new(input: &str) -> Self {
+        let mut lines = input.trim().lines().map(|line| line.trim());
+
+        let template: Vec<char> = lines
+            .next()
+            .expect("failed to extract the polymer template")
+            .chars()
+            .collect();
+
+        let rules: Vec<([char; 2], char)> = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (pair, element) = line.split_once(" -> ").expect("expected AB -> C");
+                let pair: Vec<char> = pair.chars().collect();
+                let element: Vec<char> = element.chars().collect();
+                assert!(
+                    pair.len() == 2 && element.len() == 1,
+                    "invalid insertion rule: {}",
+                    line
+                );
+                ([pair[0], pair[1]], element[0])
+            })
+            .collect();
+
+        let mut ids: HashMap<char, usize> = HashMap::new();
+        let mut elements: Vec<char> = vec![];
+        for element in template.iter().chain(
+            rules
+                .iter()
+                .flat_map(|(pair, element)| pair.iter().chain([element])),
+        ) {
+            ids.entry(*element).or_insert_with(|| {
+                elements.push(*element);
+                elements.len() - 1
+            });
+        }
+
+        let template: Vec<usize> = template.iter().map(|element| ids[element]).collect();
+
+        let len = elements.len();
+        let mut pair_counts = vec![0; len * len];
+        for pair in template.windows(2) {
+            pair_counts[pair[0] * len + pair[1]] += 1;
+        }
+
+        let mut insertions = vec![None; len * len];
+        for ([first, second], element) in rules {
+            insertions[ids[&first] * len + ids[&second]] = Some(ids[&element]);
+        }
+
+        let last_element = *template.last().expect("the polymer template was empty");
+
+        Self {
+            elements,
+            pair_counts,
+            insertions,
+            template,
+            last_element,
+            steps_taken: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn step(&mut self) -> Result<(), CountOverflow> {
+        let len = self.len();
+        let overflow = CountOverflow {
+            step: self.steps_taken + 1,
+        };
+        let mut pair_counts = vec![0u128; len * len];
+
+        for (pair, count) in self.pair_counts.iter().enumerate() {
+            if *count == 0 {
+                continue;
+            }
+
+            let (first, second) = (pair / len, pair % len);
+            let new_pairs = match self.insertions[pair] {
+                Some(element) => vec![first * len + element, element * len + second],
+                None => vec![pair],
+            };
+
+            for new_pair in new_pairs {
+                pair_counts[new_pair] =
+                    pair_counts[new_pair].checked_add(*count).ok_or(overflow)?;
+            }
+        }
+
+        self.pair_counts = pair_counts;
+        self.steps_taken += 1;
+
+        Ok(())
+    }
+
+    // runs the given number of steps, returning the element histogram after each of them
+    pub fn run(&mut self, steps: usize) -> Result<Vec<Histogram>, CountOverflow> {
+        (0..steps)
+            .map(|_| {
+                self.step()?;
+                self.histogram()
+            })
+            .collect()
+    }
+
+    pub fn histogram(&self) -> Result<Histogram, CountOverflow> {
+        let len = self.len();
+        let overflow = CountOverflow {
+            step: self.steps_taken,
+        };
+
+        let mut counts = vec![0u128; len];
+        counts[self.last_element] = 1;
+        for (pair, count) in self.pair_counts.iter().enumerate() {
+            let first = pair / len;
+            counts[first] = counts[first].checked_add(*count).ok_or(overflow)?;
+        }
+
+        Ok(self
+            .elements
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|(element, count)| (*element, count))
+            .collect())
+    }
+
+    // the count of the most common element minus the count of the least common one
+    pub fn most_minus_least(&self) -> Result<u128, CountOverflow> {
+        let histogram = self.histogram()?;
+        let max = histogram.values().max().unwrap();
+        let min = histogram.values().min().unwrap();
+
+        Ok(max - min)
+    }
+
+    // Returns the pairs with insertion rules that can never apply, because no sequence of
+    // insertions starting from the template ever produces them.
+    pub fn unreachable_rules(&self) -> Vec<String> {
+        let len = self.len();
+        let mut reachable: Vec<bool> = self.pair_counts.iter().map(|count| *count > 0).collect();
+        let mut to_visit: Vec<usize> = (0..len * len).filter(|pair| reachable[*pair]).collect();
+
+        while let Some(pair) = to_visit.pop() {
+            if let Some(element) = self.insertions[pair] {
+                let (first, second) = (pair / len, pair % len);
+                for new_pair in [first * len + element, element * len + second] {
+                    if !reachable[new_pair] {
+                        reachable[new_pair] = true;
+                        to_visit.push(new_pair);
+                    }
+                }
+            }
+        }
+
+        let mut unreachable: Vec<String> = (0..len * len)
+            .filter(|pair| self.insertions[*pair].is_some() && !reachable[*pair])
+            .map(|pair| {
+                [self.elements[pair / len], self.elements[pair % len]]
+                    .iter()
+                    .collect()
+            })
+            .collect();
+        unreachable.sort();
+
+        unreachable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        NNCB
+
+        CH -> B
+        HH -> N
+        CB -> H
+        NH -> C
+        HB -> C
+        HC -> B
+        HN -> C
+        NN -> C
+        BH -> H
+        NC -> B
+        NB -> B
+        BN -> B
+        BB -> N
+        BC -> B
+        CC -> N
+        CN -> C
+    ";
+
+    fn histogram_of(polymer: &str) -> Histogram {
+        let mut histogram = Histogram::new();
+        for element in polymer.chars() {
+            *histogram.entry(element).or_default() += 1;
+        }
+        histogram
+    }
+
+    #[test]
+    fn histograms_after_each_step() {
+        let mut polymer = Polymer::new(TEST_INPUT);
+
+        assert_eq!(polymer.histogram(), Ok(histogram_of("NNCB")));
+        assert_eq!(
+            polymer.run(4),
+            Ok(vec![
+                histogram_of("NCNBCHB"),
+                histogram_of("NBCCNBBBCBHCB"),
+                histogram_of("NBBBCNCCNBBNBNBBCHBHHBCHB"),
+                histogram_of("NBBNBNBBCCNBCNCCNBBNBBNBBBNBBNBBCBHCBHHNHCBBCBHCB"),
+            ])
+        );
+    }
+
+    #[test]
+    fn most_minus_least() {
+        let mut polymer = Polymer::new(TEST_INPUT);
+
+        polymer.run(10).unwrap();
+        assert_eq!(polymer.histogram().unwrap()[&'B'], 1749);
+        assert_eq!(polymer.most_minus_least(), Ok(1588));
+
+        polymer.run(30).unwrap();
+        assert_eq!(polymer.most_minus_least(), Ok(2188189693529));
+    }
+
+    #[test]
+    fn unreachable_rules() {
+        let polymer = Polymer::new(TEST_INPUT);
+        assert!(polymer.unreachable_rules().is_empty());
+
+        let polymer = Polymer::new("AB\n\nAB -> A\nBA -> C\nXY -> Z");
+        assert_eq!(polymer.unreachable_rules(), vec!["BA", "XY"]);
+    }
+
+    #[test]
+    fn overflow() {
+        let mut polymer = Polymer::new(TEST_INPUT);
+
+        assert_eq!(polymer.run(200), Err(CountOverflow { step: 128 }));
+    }
+}
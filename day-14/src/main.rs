@@ -1,81 +1,75 @@
 // Advent of Code 2021: Day 14
 // https://adventofcode.com/2021/day/14
-// Usage `cargo run <input-file> <steps>
+// Usage `cargo run <input-file> <steps> [--report] [--modulo <modulus>] [--expand]`
+//
+// --report prints the element histogram after every step and any rules which can never apply
+// --modulo prints the element counts modulo the given number instead, which works for huge step
+//   counts like 1000000000000
+// --expand prints the polymer itself, which is only practical for small step counts
 
-use std::{collections::HashMap, env, fs::File, io::BufRead, io::BufReader};
+use day_14::{CountOverflow, Polymer};
+use std::{env, fs, process};
+
+fn overflowed<T>(error: CountOverflow) -> T {
+    eprintln!(
+        "Could not count the elements: {} (try --modulo for this many steps)",
+        error
+    );
+    process::exit(1);
+}
 
 fn main() {
-    let input_file = env::args().nth(1).expect("please supply the input file");
-    let steps = env::args()
-        .nth(2)
+    let args: Vec<String> = env::args().collect();
+    let input_file = args.get(1).expect("please supply the input file");
+    let steps = args
+        .get(2)
         .expect("please supply the number of steps")
-        .parse::<usize>()
+        .parse::<u64>()
         .expect("steps must be a positive integer");
-    let file = File::open(input_file).expect("no such file");
-    let reader = BufReader::new(file);
-    let mut lines_iter = reader.lines().map(|l| l.expect("could not parse line"));
-
-    let polymer_template: Vec<char> = lines_iter
-        .next()
-        .expect("failed to extract the polymer template")
-        .chars()
-        .collect();
+    let report = args.iter().any(|arg| arg == "--report");
+    let expand = args.iter().any(|arg| arg == "--expand");
+    let modulus: Option<u64> = args.iter().position(|arg| arg == "--modulo").map(|i| {
+        args.get(i + 1)
+            .expect("please supply a modulus")
+            .parse()
+            .expect("the modulus must be a positive integer")
+    });
 
-    lines_iter.next(); // skip the blank line
+    let input = fs::read_to_string(input_file).expect("no such file");
+    let mut polymer = Polymer::new(&input);
 
-    let insertion_rules_vec: Vec<(Vec<char>, char)> = lines_iter
-        .map(|s| {
-            let parts: Vec<&str> = s.split(" -> ").collect();
-            (
-                parts[0].chars().collect(),
-                parts[1]
-                    .chars()
-                    .nth(0)
-                    .expect("failed to grab the char to insert"),
-            )
-        })
-        .collect();
-
-    let mut pair_counts: HashMap<(char, char), usize> = HashMap::new();
-    for pair in polymer_template.windows(2) {
-        *pair_counts.entry((pair[0], pair[1])).or_default() += 1;
+    if expand {
+        println!("{}", polymer.expand(steps as usize));
     }
 
-    let mut insertion_rules: HashMap<(char, char), char> = HashMap::new();
-    for (pair, char) in insertion_rules_vec {
-        insertion_rules.insert((pair[0], pair[1]), char);
+    if let Some(modulus) = modulus {
+        println!(
+            "element counts modulo {} after {} steps: {:?}",
+            modulus,
+            steps,
+            polymer.histogram_after(steps, modulus)
+        );
+        return;
     }
 
-    let mut element_counts: HashMap<char, usize> = HashMap::new();
-    for element in polymer_template {
-        *element_counts.entry(element).or_default() += 1;
+    if report {
+        for pair in polymer.unreachable_rules() {
+            println!("the rule for {} can never apply", pair);
+        }
     }
 
-    for _ in 0..steps {
-        let mut changes: HashMap<(char, char), isize> = HashMap::new();
-
-        for pair in pair_counts.keys() {
-            let (first, second) = *pair;
-            if let Some(element) = insertion_rules.get(&(first, second)) {
-                let pair_count = *pair_counts.get(&(first, second)).unwrap();
-
-                *changes.entry((first, *element)).or_default() += pair_count as isize;
-                *changes.entry((*element, second)).or_default() += pair_count as isize;
-                *changes.entry((first, second)).or_default() -= pair_count as isize;
-
-                *element_counts.entry(*element).or_default() += pair_count;
-            }
+    if report {
+        let histograms = polymer.run(steps as usize).unwrap_or_else(overflowed);
+        for (step, histogram) in histograms.iter().enumerate() {
+            println!("after step {}: {:?}", step + 1, histogram);
         }
-
-        for ((first, second), change) in changes.iter() {
-            let initial_count = *pair_counts.get(&(*first, *second)).unwrap_or(&0) as isize;
-            let new_count = (initial_count + change) as usize;
-            pair_counts.insert((*first, *second), new_count);
+    } else {
+        for _ in 0..steps {
+            polymer.step().unwrap_or_else(overflowed);
         }
     }
 
-    let max = element_counts.values().max().unwrap();
-    let min = element_counts.values().min().unwrap();
+    let result = polymer.most_minus_least().unwrap_or_else(overflowed);
 
-    println!("result: {}", max - min);
+    println!("result: {}", result);
 }
@@ -0,0 +1,268 @@
+// The pair counts after a step are a linear function of the pair counts before it, so applying
+// the transition matrix n times can be done with O(log n) matrix multiplications instead. The
+// counts grow exponentially, so for huge step counts only their values modulo some number can be
+// calculated.
+
+use crate::Polymer;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    pub size: usize,
+    // row-major values, all less than the modulus
+    pub values: Vec<u64>,
+    pub modulus: u64,
+}
+
+impl Matrix {
+    pub fn identity(size: usize, modulus: u64) -> Self {
+        let mut values = vec![0; size * size];
+        for i in 0..size {
+            values[i * size + i] = 1 % modulus;
+        }
+
+        Self {
+            size,
+            values,
+            modulus,
+        }
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        let size = self.size;
+        let modulus = self.modulus as u128;
+        let mut values = vec![0; size * size];
+
+        for row in 0..size {
+            for k in 0..size {
+                let a = self.values[row * size + k] as u128;
+                if a == 0 {
+                    continue;
+                }
+                for col in 0..size {
+                    let b = other.values[k * size + col] as u128;
+                    let value = &mut values[row * size + col];
+                    *value = ((*value as u128 + a * b % modulus) % modulus) as u64;
+                }
+            }
+        }
+
+        Self {
+            size,
+            values,
+            modulus: self.modulus,
+        }
+    }
+
+    // raises the matrix to the given power by repeated squaring
+    pub fn pow(&self, mut exponent: u64) -> Self {
+        let mut result = Self::identity(self.size, self.modulus);
+        let mut square = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply(&square);
+            }
+            square = square.multiply(&square);
+            exponent >>= 1;
+        }
+
+        result
+    }
+
+    pub fn apply(&self, vector: &[u64]) -> Vec<u64> {
+        let modulus = self.modulus as u128;
+
+        (0..self.size)
+            .map(|row| {
+                vector.iter().enumerate().fold(0u128, |sum, (col, value)| {
+                    (sum + self.values[row * self.size + col] as u128 * *value as u128 % modulus)
+                        % modulus
+                }) as u64
+            })
+            .collect()
+    }
+}
+
+impl Polymer {
+    // the matrix taking the pair counts before a step to the pair counts after it
+    pub fn transition_matrix(&self, modulus: u64) -> Matrix {
+        let len = self.len();
+        let size = len * len;
+        let mut matrix = Matrix {
+            size,
+            values: vec![0; size * size],
+            modulus,
+        };
+
+        for pair in 0..size {
+            let (first, second) = (pair / len, pair % len);
+            let new_pairs = match self.insertions[pair] {
+                Some(element) => vec![first * len + element, element * len + second],
+                None => vec![pair],
+            };
+            for new_pair in new_pairs {
+                let value = &mut matrix.values[new_pair * size + pair];
+                *value = (*value + 1) % modulus;
+            }
+        }
+
+        matrix
+    }
+
+    // The element counts modulo `modulus` after `steps` steps, starting from the template. Every
+    // element in the template or rules is included, even if its count is zero.
+    pub fn histogram_after(&self, steps: u64, modulus: u64) -> BTreeMap<char, u64> {
+        assert!(modulus > 0, "the modulus must be positive");
+
+        let len = self.len();
+        let mut initial_pair_counts = vec![0; len * len];
+        for pair in self.template.windows(2) {
+            let pair_count = &mut initial_pair_counts[pair[0] * len + pair[1]];
+            *pair_count = (*pair_count + 1) % modulus;
+        }
+
+        let pair_counts = self
+            .transition_matrix(modulus)
+            .pow(steps)
+            .apply(&initial_pair_counts);
+
+        let mut histogram: BTreeMap<char, u64> = BTreeMap::new();
+        *histogram
+            .entry(self.elements[self.last_element])
+            .or_default() = 1 % modulus;
+        for (pair, pair_count) in pair_counts.iter().enumerate() {
+            let count = histogram.entry(self.elements[pair / len]).or_default();
+            *count = ((*count as u128 + *pair_count as u128) % modulus as u128) as u64;
+        }
+
+        histogram
+    }
+
+    // Builds the actual polymer after `steps` steps, starting from the template. Its length
+    // roughly doubles with every step, so this is only practical for small step counts.
+    pub fn expand(&self, steps: usize) -> String {
+        let len = self.len();
+        let mut polymer = self.template.clone();
+
+        for _ in 0..steps {
+            let mut expanded = Vec::with_capacity(polymer.len() * 2);
+            for pair in polymer.windows(2) {
+                expanded.push(pair[0]);
+                if let Some(element) = self.insertions[pair[0] * len + pair[1]] {
+                    expanded.push(element);
+                }
+            }
+            expanded.push(self.last_element);
+            polymer = expanded;
+        }
+
+        polymer.iter().map(|id| self.elements[*id]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        NNCB
+
+        CH -> B
+        HH -> N
+        CB -> H
+        NH -> C
+        HB -> C
+        HC -> B
+        HN -> C
+        NN -> C
+        BH -> H
+        NC -> B
+        NB -> B
+        BN -> B
+        BB -> N
+        BC -> B
+        CC -> N
+        CN -> C
+    ";
+
+    fn pow_mod(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+        let (mut result, mut base) = (1u128, base as u128 % modulus as u128);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base % modulus as u128;
+            }
+            base = base * base % modulus as u128;
+            exponent >>= 1;
+        }
+        result as u64
+    }
+
+    #[test]
+    fn expand() {
+        let polymer = Polymer::new(TEST_INPUT);
+
+        assert_eq!(polymer.expand(0), "NNCB");
+        assert_eq!(polymer.expand(2), "NBCCNBBBCBHCB");
+        assert_eq!(polymer.expand(5).len(), 97);
+    }
+
+    #[test]
+    fn matches_explicit_expansion() {
+        let polymer = Polymer::new(TEST_INPUT);
+        let modulus = 1_000_000_007;
+
+        for steps in 0..12 {
+            let expanded = polymer.expand(steps);
+            for (element, count) in polymer.histogram_after(steps as u64, modulus) {
+                let expected = expanded.chars().filter(|c| *c == element).count() as u64;
+                assert_eq!(
+                    count,
+                    expected % modulus,
+                    "{} after {} steps",
+                    element,
+                    steps
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn matches_step_by_step() {
+        let mut polymer = Polymer::new(TEST_INPUT);
+        let histogram = polymer.histogram_after(40, u64::MAX);
+
+        polymer.run(40).unwrap();
+        for (element, count) in polymer.histogram().unwrap() {
+            assert_eq!(histogram[&element] as u128, count);
+        }
+    }
+
+    #[test]
+    fn huge_step_count() {
+        // every pair has a rule, so the polymer length after n steps is 3 * 2^n + 1
+        let polymer = Polymer::new(TEST_INPUT);
+        let modulus = 998_244_353;
+        let steps = 1_000_000_000_000;
+
+        let total = polymer
+            .histogram_after(steps, modulus)
+            .values()
+            .fold(0, |total, count| (total + count) % modulus);
+
+        assert_eq!(total, (3 * pow_mod(2, steps, modulus) + 1) % modulus);
+    }
+
+    #[test]
+    fn matrix_pow() {
+        let matrix = Matrix {
+            size: 2,
+            values: vec![1, 1, 1, 0],
+            modulus: 1_000,
+        };
+
+        // fibonacci numbers, F(30) = 832040
+        assert_eq!(matrix.pow(30).values, vec![269, 40, 40, 229]);
+        assert_eq!(matrix.pow(0), Matrix::identity(2, 1_000));
+    }
+}
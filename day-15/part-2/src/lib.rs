@@ -0,0 +1,288 @@
+pub mod render;
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+pub type Position = (usize, usize);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Search {
+    Dijkstra,
+    // A* using the manhattan distance to the end, which never overestimates the remaining risk
+    // since every cell has a risk level of at least 1
+    AStar,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Path {
+    pub total_risk: usize,
+    // every position along the path, from the top left to the bottom right
+    pub positions: Vec<Position>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Cell {
+    // the cost so far plus the estimated cost to the end
+    priority: usize,
+    cost: usize,
+    position: Position,
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// The full map is the input tile repeated tile_factor times in each direction, with every tile
+// one risk level higher than the tile above or to the left of it. Risk levels above 9 wrap back
+// around to 1. Tiled risk levels are calculated on demand rather than building the full map.
+pub struct RiskMap {
+    tile: Vec<Vec<usize>>,
+    tile_width: usize,
+    tile_height: usize,
+    pub tile_factor: usize,
+}
+
+impl RiskMap {
+    pub fn new(input: &str) -> Self {
+        let tile: Vec<Vec<usize>> = input
+            .trim()
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .chars()
+                    .map(|c| c.to_digit(10).expect("not a digit") as usize)
+                    .collect()
+            })
+            .collect();
+
+        let tile_height = tile.len();
+        let tile_width = tile.first().map_or(0, |row| row.len());
+
+        Self {
+            tile,
+            tile_width,
+            tile_height,
+            tile_factor: 1,
+        }
+    }
+
+    pub fn with_tile_factor(mut self, tile_factor: usize) -> Self {
+        assert!(tile_factor > 0, "the tile factor must be at least 1");
+        self.tile_factor = tile_factor;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.tile_width * self.tile_factor
+    }
+
+    pub fn height(&self) -> usize {
+        self.tile_height * self.tile_factor
+    }
+
+    pub fn risk_level(&self, (row, col): Position) -> usize {
+        let increase = row / self.tile_height + col / self.tile_width;
+        let risk_level = self.tile[row % self.tile_height][col % self.tile_width] + increase;
+
+        (risk_level - 1) % 9 + 1
+    }
+
+    fn neighbors(&self, (row, col): Position) -> Vec<Position> {
+        let mut neighbors: Vec<Position> = vec![];
+
+        if row > 0 {
+            neighbors.push((row - 1, col));
+        }
+        if col + 1 < self.width() {
+            neighbors.push((row, col + 1));
+        }
+        if row + 1 < self.height() {
+            neighbors.push((row + 1, col));
+        }
+        if col > 0 {
+            neighbors.push((row, col - 1));
+        }
+
+        neighbors
+    }
+
+    fn index(&self, (row, col): Position) -> usize {
+        row * self.width() + col
+    }
+
+    // finds the path from the top left to the bottom right with the lowest total risk, not
+    // counting the risk level of the starting position, or None if the map is empty
+    pub fn lowest_risk_path(&self, search: Search) -> Option<Path> {
+        if self.width() == 0 || self.height() == 0 {
+            return None;
+        }
+
+        let end = (self.height() - 1, self.width() - 1);
+        let estimate = |(row, col): Position| match search {
+            Search::Dijkstra => 0,
+            Search::AStar => (end.0 - row) + (end.1 - col),
+        };
+
+        let mut lowest_costs = vec![usize::MAX; self.width() * self.height()];
+        let mut came_from: Vec<Option<Position>> = vec![None; self.width() * self.height()];
+        let mut heap = BinaryHeap::new();
+
+        lowest_costs[0] = 0;
+        heap.push(Cell {
+            priority: estimate((0, 0)),
+            cost: 0,
+            position: (0, 0),
+        });
+
+        while let Some(Cell { cost, position, .. }) = heap.pop() {
+            if position == end {
+                break;
+            }
+
+            if cost > lowest_costs[self.index(position)] {
+                continue;
+            }
+
+            for neighbor in self.neighbors(position) {
+                let next_cost = cost + self.risk_level(neighbor);
+                let index = self.index(neighbor);
+
+                if next_cost < lowest_costs[index] {
+                    lowest_costs[index] = next_cost;
+                    came_from[index] = Some(position);
+                    heap.push(Cell {
+                        priority: next_cost + estimate(neighbor),
+                        cost: next_cost,
+                        position: neighbor,
+                    });
+                }
+            }
+        }
+
+        let mut positions = vec![end];
+        while let Some(previous) = came_from[self.index(*positions.last().unwrap())] {
+            positions.push(previous);
+        }
+        positions.reverse();
+
+        Some(Path {
+            total_risk: lowest_costs[self.index(end)],
+            positions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        1163751742
+        1381373672
+        2136511328
+        3694931569
+        7463417111
+        1319128137
+        1359912421
+        3125421639
+        1293138521
+        2311944581
+    ";
+
+    #[test]
+    fn tiled_risk_levels() {
+        let risk_map = RiskMap::new(TEST_INPUT).with_tile_factor(5);
+
+        assert_eq!((risk_map.width(), risk_map.height()), (50, 50));
+
+        let first_row: String = (0..50)
+            .map(|col| risk_map.risk_level((0, col)).to_string())
+            .collect();
+        assert_eq!(
+            first_row,
+            "11637517422274862853338597396444961841755517295286"
+        );
+
+        let last_row: String = (0..50)
+            .map(|col| risk_map.risk_level((49, col)).to_string())
+            .collect();
+        assert_eq!(
+            last_row,
+            "67554889357866599146897761125791887223681299833479"
+        );
+    }
+
+    #[test]
+    fn lowest_risk_path() {
+        let risk_map = RiskMap::new(TEST_INPUT);
+        let path = risk_map.lowest_risk_path(Search::Dijkstra).unwrap();
+
+        assert_eq!(path.total_risk, 40);
+        assert_eq!(path.positions.first(), Some(&(0, 0)));
+        assert_eq!(path.positions.last(), Some(&(9, 9)));
+        assert_eq!(
+            path.positions[1..]
+                .iter()
+                .map(|position| risk_map.risk_level(*position))
+                .sum::<usize>(),
+            40
+        );
+    }
+
+    #[test]
+    fn tiled_lowest_risk_path() {
+        let risk_map = RiskMap::new(TEST_INPUT).with_tile_factor(5);
+
+        assert_eq!(
+            risk_map
+                .lowest_risk_path(Search::Dijkstra)
+                .unwrap()
+                .total_risk,
+            315
+        );
+        assert_eq!(
+            risk_map.lowest_risk_path(Search::AStar).unwrap().total_risk,
+            315
+        );
+    }
+
+    #[test]
+    fn a_star_path_is_connected() {
+        let risk_map = RiskMap::new(TEST_INPUT).with_tile_factor(2);
+        let path = risk_map.lowest_risk_path(Search::AStar).unwrap();
+
+        assert_eq!(
+            path.total_risk,
+            risk_map
+                .lowest_risk_path(Search::Dijkstra)
+                .unwrap()
+                .total_risk
+        );
+        for pair in path.positions.windows(2) {
+            let ((a_row, a_col), (b_row, b_col)) = (pair[0], pair[1]);
+            assert_eq!(a_row.abs_diff(b_row) + a_col.abs_diff(b_col), 1);
+        }
+    }
+
+    #[test]
+    fn empty_map() {
+        assert_eq!(RiskMap::new("").lowest_risk_path(Search::Dijkstra), None);
+        assert_eq!(RiskMap::new("\n\n").lowest_risk_path(Search::AStar), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "the tile factor must be at least 1")]
+    fn rejects_zero_tile_factor() {
+        RiskMap::new(TEST_INPUT).with_tile_factor(0);
+    }
+}
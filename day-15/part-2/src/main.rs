@@ -1,6 +1,11 @@
 // Advent of Code 2021: Day 15, Part 2
 // https://adventofcode.com/2021/day/15
-// Usage `cargo run <input-file>
+// Usage `cargo run <input-file> [tile-factor] [--astar] [--render] [--svg <file>] [--ppm <file>]`
+//
+// tile-factor is how many times the input is repeated in each direction, 5 by default
+// --astar uses A* instead of Dijkstra's Algorithm
+// --render prints the risk map with the lowest risk path highlighted
+// --svg <file> and --ppm <file> save an image of the risk map with the path drawn over it
 //
 // TL;DR I kinda cheated on this one.
 //
@@ -22,145 +27,57 @@
 //
 // https://doc.rust-lang.org/std/collections/binary_heap/index.html
 
-use std::{cmp::Ordering, collections::BinaryHeap, env, fs::File, io::BufRead, io::BufReader};
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct Cell {
-    cost: usize,
-    position: (usize, usize),
-}
-
-impl Ord for Cell {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
-    }
-}
-
-impl PartialOrd for Cell {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-fn wrap(n: usize) -> usize {
-    let digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
-    digits[(n - 1) % 9]
-}
-
-fn neighbors(position: &(usize, usize), risk_level_grid: &Vec<Vec<usize>>) -> Vec<Cell> {
-    let (row, col) = *position;
-    let width = risk_level_grid[0].len();
-    let height = risk_level_grid.len();
-
-    let mut possible_neighbors: Vec<Cell> = vec![];
-
-    if row > 0 {
-        possible_neighbors.push(Cell {
-            position: (row - 1, col),
-            cost: risk_level_grid[row - 1][col],
-        });
-    }
-    if col + 1 < width {
-        possible_neighbors.push(Cell {
-            position: (row, col + 1),
-            cost: risk_level_grid[row][col + 1],
-        })
-    }
-    if row + 1 < height {
-        possible_neighbors.push(Cell {
-            position: (row + 1, col),
-            cost: risk_level_grid[row + 1][col],
-        })
-    }
-    if col > 0 {
-        possible_neighbors.push(Cell {
-            position: (row, col - 1),
-            cost: risk_level_grid[row][col - 1],
-        });
-    }
-
-    possible_neighbors
-}
+use part_2::render::{render_terminal, to_svg, write_ppm};
+use part_2::{RiskMap, Search};
+use std::{env, fs, fs::File, io::BufWriter, process};
 
 fn main() {
-    let input_file = env::args().nth(1).expect("please supply the input file");
-    let file = File::open(input_file).expect("no such file");
-    let reader = BufReader::new(file);
-
-    let input: Vec<Vec<usize>> = reader
-        .lines()
-        .map(|l| l.unwrap())
-        .map(|s| {
-            s.chars()
-                .map(|c| c.to_digit(10).unwrap() as usize)
-                .collect()
+    let args: Vec<String> = env::args().collect();
+    let input_file = args.get(1).expect("please supply the input file");
+    let tile_factor: usize = args
+        .get(2)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(|arg| {
+            arg.parse()
+                .ok()
+                .filter(|tile_factor| *tile_factor > 0)
+                .expect("the tile factor must be a positive integer")
         })
-        .collect();
-
-    let input_width = input[0].len();
-    let input_height = input.len();
-
-    let mut risk_level_grid: Vec<Vec<usize>> = vec![vec![0; 5 * input_width]; 5 * input_height];
-
-    for row in 0..input_height {
-        for col in 0..input_width {
-            for col_multiplier in 0..5 {
-                for row_multiplier in 0..5 {
-                    risk_level_grid[row + (row_multiplier * input_height)]
-                        [col + (col_multiplier * input_width)] =
-                        wrap(input[row][col] + col_multiplier + row_multiplier);
-                }
-            }
-        }
+        .unwrap_or(5);
+    let option_value = |option: &str| {
+        args.iter().position(|arg| arg == option).map(|i| {
+            args.get(i + 1)
+                .unwrap_or_else(|| panic!("please supply a file name for {}", option))
+        })
+    };
+    let render = args.iter().any(|arg| arg == "--render");
+    let svg_file = option_value("--svg");
+    let ppm_file = option_value("--ppm");
+    let search = if args.iter().any(|arg| arg == "--astar") {
+        Search::AStar
+    } else {
+        Search::Dijkstra
+    };
+
+    let input = fs::read_to_string(input_file).expect("no such file");
+    let risk_map = RiskMap::new(&input).with_tile_factor(tile_factor);
+    let Some(path) = risk_map.lowest_risk_path(search) else {
+        eprintln!("The risk map is empty");
+        process::exit(1);
+    };
+
+    if render {
+        print!("{}", render_terminal(&risk_map, &path));
     }
 
-    let mut lowest_costs = risk_level_grid.clone();
-
-    let width = risk_level_grid[0].len();
-    let height = risk_level_grid.len();
-
-    for row in 0..height {
-        for col in 0..width {
-            // unvisited_set.insert((row, col));
-            lowest_costs[row][col] = usize::MAX;
-        }
+    if let Some(svg_file) = svg_file {
+        fs::write(svg_file, to_svg(&risk_map, &path, 10)).expect("could not write the svg");
     }
-    lowest_costs[0][0] = 0;
-
-    let mut heap = BinaryHeap::new();
-
-    heap.push(Cell {
-        cost: 0,
-        position: (0, 0),
-    });
-
-    let mut final_cost: usize = 0;
-
-    while let Some(Cell { cost, position }) = heap.pop() {
-        if position == (height - 1, width - 1) {
-            final_cost = cost;
-            break;
-        }
-
-        if cost > lowest_costs[position.0][position.1] {
-            continue;
-        }
-
-        for neighbor in neighbors(&position, &risk_level_grid) {
-            let next = Cell {
-                cost: cost + neighbor.cost,
-                position: neighbor.position,
-            };
 
-            if next.cost < lowest_costs[next.position.0][next.position.1] {
-                heap.push(next);
-                lowest_costs[next.position.0][next.position.1] = next.cost;
-            }
-        }
+    if let Some(ppm_file) = ppm_file {
+        let file = File::create(ppm_file).expect("could not create the ppm file");
+        write_ppm(BufWriter::new(file), &risk_map, &path, 2).expect("could not write the ppm");
     }
 
-    println!("Lowest cost to end: {}", final_cost);
+    println!("Lowest cost to end: {}", path.total_risk);
 }
@@ -0,0 +1,204 @@
+use crate::{Path, Position, RiskMap};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+const RESET: &str = "\x1b[0m";
+const PATH: &str = "\x1b[1;97;41m";
+const PATH_COLOR: [u8; 3] = [220, 40, 40];
+
+// Low risk cells are drawn light and high risk cells dark, so the path should mostly run through
+// the lighter areas of the map.
+fn shade(risk_level: usize) -> u8 {
+    (255 - (risk_level.clamp(1, 9) - 1) * 25) as u8
+}
+
+fn path_positions(risk_map: &RiskMap, path: &Path) -> HashSet<Position> {
+    for (row, col) in &path.positions {
+        assert!(
+            *row < risk_map.height() && *col < risk_map.width(),
+            "path position is outside the map: ({}, {})",
+            row,
+            col
+        );
+    }
+
+    path.positions.iter().copied().collect()
+}
+
+// renders the risk map with ANSI escapes, dimming higher risk levels and highlighting the path
+pub fn render_terminal(risk_map: &RiskMap, path: &Path) -> String {
+    let on_path = path_positions(risk_map, path);
+    let mut output = String::new();
+
+    for row in 0..risk_map.height() {
+        for col in 0..risk_map.width() {
+            let risk_level = risk_map.risk_level((row, col));
+            if on_path.contains(&(row, col)) {
+                write!(output, "{}{}{}", PATH, risk_level, RESET).unwrap();
+            } else {
+                // risk levels 1 to 9 step down the top of the grayscale ramp from 255 to 239
+                let gray = 255 - (risk_level - 1) * 2;
+                write!(output, "\x1b[38;5;{}m{}{}", gray, risk_level, RESET).unwrap();
+            }
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+// Renders the risk map as an SVG with every cell `cell_size` pixels square, shaded by its risk
+// level, and the path drawn over it as a line through the centres of its cells.
+pub fn to_svg(risk_map: &RiskMap, path: &Path, cell_size: usize) -> String {
+    path_positions(risk_map, path);
+
+    let (width, height) = (risk_map.width() * cell_size, risk_map.height() * cell_size);
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" shape-rendering="crispEdges">"#,
+        width, height
+    )
+    .unwrap();
+
+    for row in 0..risk_map.height() {
+        for col in 0..risk_map.width() {
+            let gray = shade(risk_map.risk_level((row, col)));
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="rgb({3},{3},{3})"/>"#,
+                col * cell_size,
+                row * cell_size,
+                cell_size,
+                gray
+            )
+            .unwrap();
+        }
+    }
+
+    let points: Vec<String> = path
+        .positions
+        .iter()
+        .map(|(row, col)| {
+            format!(
+                "{},{}",
+                col * cell_size + cell_size / 2,
+                row * cell_size + cell_size / 2
+            )
+        })
+        .collect();
+    writeln!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="rgb({},{},{})" stroke-width="{}" stroke-linejoin="round" shape-rendering="auto"/>"#,
+        points.join(" "),
+        PATH_COLOR[0],
+        PATH_COLOR[1],
+        PATH_COLOR[2],
+        (cell_size / 3).max(1)
+    )
+    .unwrap();
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+// Writes the risk map as a binary PPM image with every cell `scale` pixels square, shaded by its
+// risk level, with the cells along the path filled in red.
+pub fn write_ppm<W: Write>(
+    mut writer: W,
+    risk_map: &RiskMap,
+    path: &Path,
+    scale: usize,
+) -> io::Result<()> {
+    let on_path = path_positions(risk_map, path);
+    let (width, height) = (risk_map.width() * scale, risk_map.height() * scale);
+
+    write!(writer, "P6\n{} {}\n255\n", width, height)?;
+
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for row in 0..risk_map.height() {
+        let mut line = Vec::with_capacity(width * 3);
+        for col in 0..risk_map.width() {
+            let color = if on_path.contains(&(row, col)) {
+                PATH_COLOR
+            } else {
+                [shade(risk_map.risk_level((row, col))); 3]
+            };
+            for _ in 0..scale {
+                line.extend_from_slice(&color);
+            }
+        }
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+
+    writer.write_all(&pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Search;
+
+    const TEST_INPUT: &str = "
+        1163751742
+        1381373672
+        2136511328
+        3694931569
+        7463417111
+        1319128137
+        1359912421
+        3125421639
+        1293138521
+        2311944581
+    ";
+
+    #[test]
+    fn terminal_highlights_path() {
+        let risk_map = RiskMap::new(TEST_INPUT);
+        let path = risk_map.lowest_risk_path(Search::Dijkstra).unwrap();
+        let output = render_terminal(&risk_map, &path);
+
+        assert_eq!(output.lines().count(), 10);
+        assert_eq!(output.matches(PATH).count(), path.positions.len());
+        assert!(output.starts_with(&format!("{}1{}", PATH, RESET)));
+    }
+
+    #[test]
+    fn ppm() {
+        let risk_map = RiskMap::new(TEST_INPUT).with_tile_factor(2);
+        let path = risk_map.lowest_risk_path(Search::AStar).unwrap();
+        let mut image = vec![];
+
+        write_ppm(&mut image, &risk_map, &path, 2).unwrap();
+
+        let header = b"P6\n40 40\n255\n";
+        assert!(image.starts_with(header));
+        assert_eq!(image.len(), header.len() + 40 * 40 * 3);
+
+        // the start is on the path, the cell to the right of it has risk level 1
+        let pixel = |x: usize, y: usize| {
+            let offset = header.len() + (y * 40 + x) * 3;
+            [image[offset], image[offset + 1], image[offset + 2]]
+        };
+        assert_eq!(pixel(1, 1), PATH_COLOR);
+        assert_eq!(pixel(3, 0), [255; 3]);
+    }
+
+    #[test]
+    fn svg() {
+        let risk_map = RiskMap::new(TEST_INPUT);
+        let path = risk_map.lowest_risk_path(Search::Dijkstra).unwrap();
+        let svg = to_svg(&risk_map, &path, 10);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), 100);
+        assert!(svg.contains(r#"<polyline points="5,5 "#));
+        assert!(svg.contains(" 95,95\""));
+    }
+}
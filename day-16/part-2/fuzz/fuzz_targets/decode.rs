@@ -0,0 +1,21 @@
+// Usage `cargo fuzz run decode` from the part-2 directory
+//
+// Decoding arbitrary text must never panic, and anything that does decode must evaluate without
+// panicking and survive being encoded and decoded again.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use part_2::Packet;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(hex_string) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(packet) = Packet::decode(hex_string) {
+        let _ = packet.eval();
+        let _ = packet.to_string();
+        assert_eq!(Packet::decode(&packet.encode()), Ok(packet));
+    }
+});
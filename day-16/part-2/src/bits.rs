@@ -0,0 +1,184 @@
+// Bit-level access to a transmission, most significant bit first, without expanding it into a
+// string of '0' and '1' characters.
+
+use crate::DecodeError;
+
+pub fn hex_to_bytes(hex_string: &str) -> Result<(Vec<u8>, usize), DecodeError> {
+    let digits: Vec<u8> = hex_string
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(position, digit)| {
+            digit
+                .to_digit(16)
+                .map(|value| value as u8)
+                .ok_or(DecodeError::InvalidHexDigit { position, digit })
+        })
+        .collect::<Result<_, _>>()?;
+
+    let bytes = digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).unwrap_or(&0))
+        .collect();
+
+    Ok((bytes, digits.len() * 4))
+}
+
+fn bit_at(bytes: &[u8], position: usize) -> bool {
+    bytes[position / 8] >> (7 - position % 8) & 1 == 1
+}
+
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    len: usize,
+    pub position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    // only the first `len` bits of `bytes` can be read
+    pub fn new(bytes: &'a [u8], len: usize) -> Self {
+        Self {
+            bytes,
+            len: len.min(bytes.len() * 8),
+            position: 0,
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    // None once the end of the transmission has been reached
+    pub fn read_bit(&mut self) -> Option<bool> {
+        if self.position >= self.len {
+            return None;
+        }
+
+        let bit = bit_at(self.bytes, self.position);
+        self.position += 1;
+
+        Some(bit)
+    }
+
+    // reads `count` bits (at most 64) as an unsigned number, or None if there aren't enough left
+    pub fn read(&mut self, count: usize) -> Option<u64> {
+        assert!(count <= 64, "can't read more than 64 bits at once");
+
+        if count > self.remaining() {
+            return None;
+        }
+
+        (0..count).try_fold(0, |value, _| Some(value << 1 | self.read_bit()? as u64))
+    }
+}
+
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    pub len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (7 - self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    // writes the lowest `count` bits of `value`, most significant first
+    pub fn write(&mut self, value: u64, count: usize) {
+        assert!(count <= 64, "can't write more than 64 bits at once");
+        assert!(
+            count == 64 || value >> count == 0,
+            "{} doesn't fit in {} bits",
+            value,
+            count
+        );
+
+        for i in (0..count).rev() {
+            self.write_bit(value >> i & 1 == 1);
+        }
+    }
+
+    pub fn append(&mut self, other: &BitWriter) {
+        for position in 0..other.len {
+            self.write_bit(bit_at(&other.bytes, position));
+        }
+    }
+
+    // the bits written so far, with the last byte padded with zeros
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_across_bytes() {
+        let (bytes, len) = hex_to_bytes("D2FE28").unwrap();
+        let mut reader = BitReader::new(&bytes, len);
+
+        assert_eq!(len, 24);
+        assert_eq!(reader.read(3), Some(6));
+        assert_eq!(reader.read(3), Some(4));
+        assert_eq!(reader.read(5), Some(0b10111));
+        assert_eq!(reader.read(5), Some(0b11110));
+        assert_eq!(reader.read(5), Some(0b00101));
+        assert_eq!(reader.remaining(), 3);
+        assert_eq!(reader.read(4), None);
+        assert_eq!(reader.read(3), Some(0));
+        assert_eq!(reader.read_bit(), None);
+    }
+
+    #[test]
+    fn odd_number_of_hex_digits() {
+        let (bytes, len) = hex_to_bytes("ABC").unwrap();
+
+        assert_eq!(bytes, vec![0xAB, 0xC0]);
+        assert_eq!(len, 12);
+    }
+
+    #[test]
+    fn invalid_hex_digit() {
+        assert_eq!(
+            hex_to_bytes("12G4"),
+            Err(DecodeError::InvalidHexDigit {
+                position: 2,
+                digit: 'G'
+            })
+        );
+    }
+
+    #[test]
+    fn write_then_read() {
+        let mut writer = BitWriter::new();
+        writer.write(0b101, 3);
+        writer.write(2021, 11);
+        writer.write(u64::MAX, 64);
+
+        let len = writer.len;
+        let bytes = writer.into_bytes();
+        let mut reader = BitReader::new(&bytes, len);
+
+        assert_eq!(bytes.len(), 10);
+        assert_eq!(reader.read(3), Some(0b101));
+        assert_eq!(reader.read(11), Some(2021));
+        assert_eq!(reader.read(64), Some(u64::MAX));
+        assert_eq!(reader.remaining(), 0);
+    }
+}
@@ -0,0 +1,430 @@
+// Packet trees written as S-expressions, like `(* (+ 1 2) (max 3 4))`. Versions and length types
+// aren't part of the expression, so parsed packets all have version 0 and give their subpacket
+// count.
+
+use crate::{LengthType, Packet, PacketType, MAX_DEPTH};
+use std::error::Error;
+use std::fmt;
+
+const OPERATORS: [(&str, PacketType); 7] = [
+    ("+", PacketType::Sum),
+    ("*", PacketType::Product),
+    ("min", PacketType::Minimum),
+    ("max", PacketType::Maximum),
+    (">", PacketType::GreaterThan),
+    ("<", PacketType::LessThan),
+    ("=", PacketType::EqualTo),
+];
+
+fn symbol(op: PacketType) -> &'static str {
+    OPERATORS
+        .iter()
+        .find(|(_, operator)| *operator == op)
+        .map(|(symbol, _)| *symbol)
+        .expect("literals don't have a symbol")
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let PacketType::Literal(value) = self.op {
+            return write!(f, "{}", value);
+        }
+
+        write!(f, "({}", symbol(self.op))?;
+        for operand in &self.operands {
+            write!(f, " {}", operand)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    UnexpectedEnd,
+    UnexpectedToken {
+        position: usize,
+        token: String,
+    },
+    UnknownOperator {
+        position: usize,
+        operator: String,
+    },
+    InvalidLiteral {
+        position: usize,
+        literal: String,
+    },
+    WrongArity {
+        position: usize,
+        operator: String,
+        operands: usize,
+    },
+    // too many subpackets to count in 11 bits and too long to measure in 15
+    TooManyOperands {
+        position: usize,
+        operator: String,
+        operands: usize,
+    },
+    // nested inside more than MAX_DEPTH operators, which couldn't be decoded again
+    TooDeep {
+        position: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "the expression ended unexpectedly"),
+            ParseError::UnexpectedToken { position, token } => {
+                write!(f, "column {}: unexpected {:?}", position + 1, token)
+            }
+            ParseError::UnknownOperator { position, operator } => {
+                write!(
+                    f,
+                    "column {}: unknown operator {:?}",
+                    position + 1,
+                    operator
+                )
+            }
+            ParseError::InvalidLiteral { position, literal } => write!(
+                f,
+                "column {}: {:?} is not a number that fits in a u64",
+                position + 1,
+                literal
+            ),
+            ParseError::WrongArity {
+                position,
+                operator,
+                operands,
+            } => write!(
+                f,
+                "column {}: {} can't take {} operands",
+                position + 1,
+                operator,
+                operands
+            ),
+            ParseError::TooManyOperands {
+                position,
+                operator,
+                operands,
+            } => write!(
+                f,
+                "column {}: {} operands for {} are too many to encode",
+                position + 1,
+                operands,
+                operator
+            ),
+            ParseError::TooDeep { position } => write!(
+                f,
+                "column {}: expressions can't be nested more than {} deep",
+                position + 1,
+                MAX_DEPTH
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// splits the expression into parentheses and atoms, along with their byte offsets
+fn tokenize(expression: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut atom_start: Option<usize> = None;
+
+    for (i, c) in expression.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if let Some(start) = atom_start.take() {
+                tokens.push((start, &expression[start..i]));
+            }
+            if !c.is_whitespace() {
+                tokens.push((i, &expression[i..i + 1]));
+            }
+        } else if atom_start.is_none() {
+            atom_start = Some(i);
+        }
+    }
+    if let Some(start) = atom_start {
+        tokens.push((start, &expression[start..]));
+    }
+
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, &'a str)>,
+    next: usize,
+    // how many operators the next packet is inside
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next_token(&mut self) -> Result<(usize, &'a str), ParseError> {
+        let token = *self
+            .tokens
+            .get(self.next)
+            .ok_or(ParseError::UnexpectedEnd)?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.next).map(|(_, token)| *token)
+    }
+
+    fn parse_packet(&mut self) -> Result<Packet, ParseError> {
+        let (position, token) = self.next_token()?;
+        if self.depth > MAX_DEPTH {
+            return Err(ParseError::TooDeep { position });
+        }
+
+        match token {
+            "(" => self.parse_operator(),
+            ")" => Err(ParseError::UnexpectedToken {
+                position,
+                token: token.to_string(),
+            }),
+            literal => literal
+                .parse()
+                .map(|value| Packet::literal(0, value))
+                .map_err(|_| ParseError::InvalidLiteral {
+                    position,
+                    literal: literal.to_string(),
+                }),
+        }
+    }
+
+    fn parse_operator(&mut self) -> Result<Packet, ParseError> {
+        let (position, operator) = self.next_token()?;
+        let op = OPERATORS
+            .iter()
+            .find(|(symbol, _)| *symbol == operator)
+            .map(|(_, op)| *op)
+            .ok_or_else(|| ParseError::UnknownOperator {
+                position,
+                operator: operator.to_string(),
+            })?;
+
+        let mut operands = vec![];
+        self.depth += 1;
+        while self.peek() != Some(")") {
+            if self.peek().is_none() {
+                return Err(ParseError::UnexpectedEnd);
+            }
+            operands.push(self.parse_packet()?);
+        }
+        self.depth -= 1;
+        self.next += 1;
+
+        if !op.accepts_operands(operands.len()) {
+            return Err(ParseError::WrongArity {
+                position,
+                operator: operator.to_string(),
+                operands: operands.len(),
+            });
+        }
+
+        let length_type = if LengthType::SubPackets.fits(operands.len()) {
+            LengthType::SubPackets
+        } else if LengthType::Bits.fits(
+            operands
+                .iter()
+                .map(|operand| operand.bit_len().expect("parsed packets always encode"))
+                .sum(),
+        ) {
+            LengthType::Bits
+        } else {
+            return Err(ParseError::TooManyOperands {
+                position,
+                operator: operator.to_string(),
+                operands: operands.len(),
+            });
+        };
+
+        Ok(Packet::operator(0, op, length_type, operands))
+    }
+}
+
+impl Packet {
+    pub fn parse_expression(expression: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(expression),
+            next: 0,
+            depth: 0,
+        };
+        let packet = parser.parse_packet()?;
+
+        match parser.tokens.get(parser.next) {
+            Some((position, token)) => Err(ParseError::UnexpectedToken {
+                position: *position,
+                token: token.to_string(),
+            }),
+            None => Ok(packet),
+        }
+    }
+}
+
+// Compiles an expression into a hex transmission. Parsing already rejects anything too deep or
+// with too many operands to encode, and parsed packets are all version 0.
+pub fn compile(expression: &str) -> Result<String, ParseError> {
+    Ok(Packet::parse_expression(expression)?
+        .encode()
+        .expect("parsed packets always encode"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EvalError;
+
+    #[test]
+    fn display() {
+        assert_eq!(Packet::decode("D2FE28").unwrap().to_string(), "2021");
+        assert_eq!(
+            Packet::decode("9C0141080250320F1802104A08")
+                .unwrap()
+                .to_string(),
+            "(= (+ 1 3) (* 2 2))"
+        );
+        assert_eq!(
+            Packet::decode("EE00D40C823060").unwrap().to_string(),
+            "(max 1 2 3)"
+        );
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let expression = "(* (+ 1 2) (max 3 4) (< 5 (min 6 7)))";
+        let packet = Packet::parse_expression(expression).unwrap();
+
+        assert_eq!(packet.to_string(), expression);
+        assert_eq!(packet.eval(), Ok(12));
+    }
+
+    #[test]
+    fn compile_and_decode() {
+        let hex_string = compile("(* (+ 1 2) (max 3 4))").unwrap();
+        let packet = Packet::decode(&hex_string).unwrap();
+
+        assert_eq!(packet.to_string(), "(* (+ 1 2) (max 3 4))");
+        assert_eq!(packet.eval(), Ok(12));
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(
+            Packet::parse_expression("  (+\n 1\t(min 2 3) )  "),
+            Packet::parse_expression("(+ 1 (min 2 3))")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Packet::parse_expression("(+ 1 2"),
+            Err(ParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Packet::parse_expression("(+ 1 2))"),
+            Err(ParseError::UnexpectedToken {
+                position: 7,
+                token: ")".to_string()
+            })
+        );
+        assert_eq!(
+            Packet::parse_expression("(- 1 2)"),
+            Err(ParseError::UnknownOperator {
+                position: 1,
+                operator: "-".to_string()
+            })
+        );
+        assert_eq!(
+            Packet::parse_expression("(+ 1 x)"),
+            Err(ParseError::InvalidLiteral {
+                position: 5,
+                literal: "x".to_string()
+            })
+        );
+        assert_eq!(
+            Packet::parse_expression("(< 1 2 3)"),
+            Err(ParseError::WrongArity {
+                position: 1,
+                operator: "<".to_string(),
+                operands: 3
+            })
+        );
+        assert_eq!(
+            Packet::parse_expression("(max)"),
+            Err(ParseError::WrongArity {
+                position: 1,
+                operator: "max".to_string(),
+                operands: 0
+            })
+        );
+    }
+
+    #[test]
+    fn many_operands() {
+        let sum = |operands: usize| format!("(+{})", " 1".repeat(operands));
+
+        // the subpacket count only goes up to 2047, after that the length has to be in bits
+        let packet = Packet::parse_expression(&sum(2047)).unwrap();
+        assert_eq!(packet.length_type, Some(LengthType::SubPackets));
+
+        let packet = Packet::parse_expression(&sum(2048)).unwrap();
+        assert_eq!(packet.length_type, Some(LengthType::Bits));
+        let decoded = Packet::decode(&compile(&sum(2048)).unwrap()).unwrap();
+        assert_eq!(decoded, packet);
+        assert_eq!(decoded.eval(), Ok(2048));
+
+        // each literal 1 is 11 bits, and 2979 of them is too long for a 15 bit length
+        assert!(compile(&sum(2978)).is_ok());
+        assert_eq!(
+            compile(&sum(2979)),
+            Err(ParseError::TooManyOperands {
+                position: 1,
+                operator: "+".to_string(),
+                operands: 2979
+            })
+        );
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |depth: usize| format!("{}1{}", "(+ ".repeat(depth), ")".repeat(depth));
+
+        let decoded = Packet::decode(&compile(&nested(MAX_DEPTH)).unwrap()).unwrap();
+        assert_eq!(decoded.eval(), Ok(1));
+        assert_eq!(
+            compile(&nested(MAX_DEPTH + 1)),
+            Err(ParseError::TooDeep {
+                position: (MAX_DEPTH + 1) * 3
+            })
+        );
+        // deep enough to overflow the stack without the limit
+        assert!(matches!(
+            compile(&nested(1_000_000)),
+            Err(ParseError::TooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn overflow() {
+        let packet = Packet::parse_expression("(+ 1 (* 4294967296 4294967296))").unwrap();
+        assert_eq!(
+            packet.eval(),
+            Err(EvalError::Overflow {
+                op: PacketType::Product
+            })
+        );
+
+        let packet = Packet::parse_expression("(+ 18446744073709551615 1)").unwrap();
+        assert_eq!(
+            packet.eval(),
+            Err(EvalError::Overflow {
+                op: PacketType::Sum
+            })
+        );
+
+        let packet = Packet::parse_expression("(+ 18446744073709551614 1)").unwrap();
+        assert_eq!(packet.eval(), Ok(u64::MAX));
+    }
+}
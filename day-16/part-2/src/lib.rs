@@ -0,0 +1,846 @@
+pub mod bits;
+pub mod expression;
+
+use bits::{bytes_to_hex, hex_to_bytes, BitReader, BitWriter};
+use std::error::Error;
+use std::fmt::{self, Write};
+
+const VERSION_LEN: usize = 3;
+const TYPE_LEN: usize = 3;
+const LITERAL_CHUNK_LEN: usize = 5;
+const LITERAL_TYPE_ID: u8 = 4;
+// how many operators deep a packet can be, so decoding doesn't run out of stack
+pub const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PacketType {
+    Literal(u64),
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl PacketType {
+    pub fn from_type_id(type_id: u8) -> Option<Self> {
+        match type_id {
+            LITERAL_TYPE_ID => Some(Self::Literal(0)),
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Minimum),
+            3 => Some(Self::Maximum),
+            5 => Some(Self::GreaterThan),
+            6 => Some(Self::LessThan),
+            7 => Some(Self::EqualTo),
+            _ => None,
+        }
+    }
+
+    pub fn type_id(&self) -> u8 {
+        match self {
+            Self::Literal(_) => LITERAL_TYPE_ID,
+            Self::Sum => 0,
+            Self::Product => 1,
+            Self::Minimum => 2,
+            Self::Maximum => 3,
+            Self::GreaterThan => 5,
+            Self::LessThan => 6,
+            Self::EqualTo => 7,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Literal(_) => "literal",
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Minimum => "minimum",
+            Self::Maximum => "maximum",
+            Self::GreaterThan => "greater than",
+            Self::LessThan => "less than",
+            Self::EqualTo => "equal to",
+        }
+    }
+
+    // comparisons take exactly two operands, the other operators at least one
+    pub fn accepts_operands(&self, operands: usize) -> bool {
+        match self {
+            Self::Literal(_) => operands == 0,
+            Self::GreaterThan | Self::LessThan | Self::EqualTo => operands == 2,
+            _ => operands > 0,
+        }
+    }
+}
+
+// how an operator packet gives the size of its subpackets
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LengthType {
+    // the total length in bits of the subpackets
+    Bits,
+    // the number of subpackets
+    SubPackets,
+}
+
+impl LengthType {
+    const BITS_FOR_LENGTH: usize = 15;
+    const BITS_FOR_SUBPACKETS: usize = 11;
+
+    fn from_bit(bit: bool) -> Self {
+        match bit {
+            false => Self::Bits,
+            true => Self::SubPackets,
+        }
+    }
+
+    fn bit(&self) -> bool {
+        *self == Self::SubPackets
+    }
+
+    fn field_len(&self) -> usize {
+        match self {
+            Self::Bits => Self::BITS_FOR_LENGTH,
+            Self::SubPackets => Self::BITS_FOR_SUBPACKETS,
+        }
+    }
+
+    // whether the length field is big enough for this length
+    fn fits(&self, length: usize) -> bool {
+        length < 1 << self.field_len()
+    }
+}
+
+// Positions are bit offsets into the transmission, except for invalid hex digits where they're
+// character offsets.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    InvalidHexDigit {
+        position: usize,
+        digit: char,
+    },
+    Truncated {
+        position: usize,
+    },
+    InvalidTypeId {
+        position: usize,
+        type_id: u8,
+    },
+    LiteralTooLarge {
+        position: usize,
+    },
+    WrongArity {
+        position: usize,
+        op: PacketType,
+        operands: usize,
+    },
+    // the subpackets of an operator with a length in bits ran past the end of that length
+    LengthMismatch {
+        position: usize,
+        expected_end: usize,
+        actual_end: usize,
+    },
+    NonZeroPadding {
+        position: usize,
+    },
+    // the packet is inside more than MAX_DEPTH operators
+    TooDeep {
+        position: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHexDigit { position, digit } => {
+                write!(
+                    f,
+                    "{:?} at character {} is not a hex digit",
+                    digit, position
+                )
+            }
+            DecodeError::Truncated { position } => {
+                write!(f, "the transmission ended unexpectedly at bit {}", position)
+            }
+            DecodeError::InvalidTypeId { position, type_id } => {
+                write!(f, "bit {}: unknown packet type id {}", position, type_id)
+            }
+            DecodeError::LiteralTooLarge { position } => {
+                write!(f, "bit {}: the literal doesn't fit in a u64", position)
+            }
+            DecodeError::WrongArity {
+                position,
+                op,
+                operands,
+            } => write!(
+                f,
+                "bit {}: a {} packet can't have {} subpackets",
+                position,
+                op.name(),
+                operands
+            ),
+            DecodeError::LengthMismatch {
+                position,
+                expected_end,
+                actual_end,
+            } => write!(
+                f,
+                "bit {}: the subpackets should end at bit {} but end at bit {}",
+                position, expected_end, actual_end
+            ),
+            DecodeError::NonZeroPadding { position } => {
+                write!(f, "the padding after bit {} isn't all zeros", position)
+            }
+            DecodeError::TooDeep { position } => write!(
+                f,
+                "bit {}: packets can't be nested more than {} deep",
+                position, MAX_DEPTH
+            ),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+// Packets built by hand can hold values that the transmission format has no room for.
+#[derive(Debug, PartialEq)]
+pub enum EncodeError {
+    VersionTooLarge {
+        version: u8,
+    },
+    // the subpacket count or length in bits doesn't fit in the operator's length field
+    LengthTooLarge {
+        op: PacketType,
+        length_type: LengthType,
+        length: usize,
+    },
+    // the packet is inside more than MAX_DEPTH operators, so it couldn't be decoded again
+    TooDeep,
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::VersionTooLarge { version } => {
+                write!(f, "version {} doesn't fit in {} bits", version, VERSION_LEN)
+            }
+            EncodeError::LengthTooLarge {
+                op,
+                length_type,
+                length,
+            } => {
+                let unit = match length_type {
+                    LengthType::Bits => "bits of subpackets",
+                    LengthType::SubPackets => "subpackets",
+                };
+                write!(
+                    f,
+                    "a {} packet with {} {} doesn't fit in a {} bit length",
+                    op.name(),
+                    length,
+                    unit,
+                    length_type.field_len()
+                )
+            }
+            EncodeError::TooDeep => {
+                write!(f, "packets can't be nested more than {} deep", MAX_DEPTH)
+            }
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+#[derive(Debug, PartialEq)]
+pub enum EvalError {
+    Overflow { op: PacketType },
+    WrongArity { op: PacketType, operands: usize },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow { op } => {
+                write!(f, "the {} of the operands doesn't fit in a u64", op.name())
+            }
+            EvalError::WrongArity { op, operands } => {
+                write!(f, "a {} packet can't have {} operands", op.name(), operands)
+            }
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Packet {
+    pub version: u8,
+    pub op: PacketType,
+    // always None for literals
+    pub length_type: Option<LengthType>,
+    pub operands: Vec<Packet>,
+}
+
+impl Packet {
+    pub fn literal(version: u8, value: u64) -> Self {
+        Self {
+            version,
+            op: PacketType::Literal(value),
+            length_type: None,
+            operands: vec![],
+        }
+    }
+
+    pub fn operator(
+        version: u8,
+        op: PacketType,
+        length_type: LengthType,
+        operands: Vec<Packet>,
+    ) -> Self {
+        Self {
+            version,
+            op,
+            length_type: Some(length_type),
+            operands,
+        }
+    }
+
+    // Decodes the outermost packet of a hex transmission. Any padding after it must be zeros.
+    pub fn decode(hex_string: &str) -> Result<Self, DecodeError> {
+        let (bytes, len) = hex_to_bytes(hex_string)?;
+        let mut reader = BitReader::new(&bytes, len);
+
+        let packet = Self::read(&mut reader, 0)?;
+
+        let end = reader.position;
+        while let Some(bit) = reader.read_bit() {
+            if bit {
+                return Err(DecodeError::NonZeroPadding { position: end });
+            }
+        }
+
+        Ok(packet)
+    }
+
+    fn read_bits(reader: &mut BitReader, count: usize) -> Result<u64, DecodeError> {
+        reader.read(count).ok_or(DecodeError::Truncated {
+            position: reader.position,
+        })
+    }
+
+    // reads the packet at the reader's position, which is inside `depth` operators
+    fn read(reader: &mut BitReader, depth: usize) -> Result<Self, DecodeError> {
+        let position = reader.position;
+        if depth > MAX_DEPTH {
+            return Err(DecodeError::TooDeep { position });
+        }
+        let version = Self::read_bits(reader, VERSION_LEN)? as u8;
+        let type_id = Self::read_bits(reader, TYPE_LEN)? as u8;
+        let op = PacketType::from_type_id(type_id)
+            .ok_or(DecodeError::InvalidTypeId { position, type_id })?;
+
+        if let PacketType::Literal(_) = op {
+            return Ok(Self::literal(version, Self::read_literal(reader)?));
+        }
+
+        let length_type = LengthType::from_bit(Self::read_bits(reader, 1)? == 1);
+        let length = Self::read_bits(reader, length_type.field_len())? as usize;
+        let mut operands = vec![];
+
+        // every packet is at least 11 bits long, so each of these loops always makes progress
+        // and finishes once it runs out of length, subpackets or transmission
+        match length_type {
+            LengthType::Bits => {
+                let expected_end = reader.position + length;
+                while reader.position < expected_end {
+                    operands.push(Self::read(reader, depth + 1)?);
+                }
+                if reader.position != expected_end {
+                    return Err(DecodeError::LengthMismatch {
+                        position,
+                        expected_end,
+                        actual_end: reader.position,
+                    });
+                }
+            }
+            LengthType::SubPackets => {
+                while operands.len() < length {
+                    operands.push(Self::read(reader, depth + 1)?);
+                }
+            }
+        }
+
+        if !op.accepts_operands(operands.len()) {
+            return Err(DecodeError::WrongArity {
+                position,
+                op,
+                operands: operands.len(),
+            });
+        }
+
+        Ok(Self::operator(version, op, length_type, operands))
+    }
+
+    fn read_literal(reader: &mut BitReader) -> Result<u64, DecodeError> {
+        let position = reader.position;
+        let chunk_len = LITERAL_CHUNK_LEN - 1;
+        let mut value: u64 = 0;
+
+        loop {
+            let more_chunks = Self::read_bits(reader, 1)? == 1;
+            let chunk = Self::read_bits(reader, chunk_len)?;
+            if value.leading_zeros() < chunk_len as u32 {
+                return Err(DecodeError::LiteralTooLarge { position });
+            }
+            value = value << chunk_len | chunk;
+            if !more_chunks {
+                return Ok(value);
+            }
+        }
+    }
+
+    // encodes the packet as a hex transmission, padded with zeros to a whole number of bytes
+    pub fn encode(&self) -> Result<String, EncodeError> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer, 0)?;
+
+        Ok(bytes_to_hex(&writer.into_bytes()))
+    }
+
+    // the number of bits the packet takes up, before padding
+    fn bit_len(&self) -> Result<usize, EncodeError> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer, 0)?;
+        Ok(writer.len)
+    }
+
+    // writes the packet, which is inside `depth` operators
+    fn write(&self, writer: &mut BitWriter, depth: usize) -> Result<(), EncodeError> {
+        if depth > MAX_DEPTH {
+            return Err(EncodeError::TooDeep);
+        }
+        if self.version as u64 >> VERSION_LEN != 0 {
+            return Err(EncodeError::VersionTooLarge {
+                version: self.version,
+            });
+        }
+        writer.write(self.version as u64, VERSION_LEN);
+        writer.write(self.op.type_id() as u64, TYPE_LEN);
+
+        if let PacketType::Literal(value) = self.op {
+            Self::write_literal(writer, value);
+            return Ok(());
+        }
+
+        let mut subpackets = BitWriter::new();
+        for operand in &self.operands {
+            operand.write(&mut subpackets, depth + 1)?;
+        }
+
+        let length_type = self.length_type.unwrap_or(LengthType::Bits);
+        let length = match length_type {
+            LengthType::Bits => subpackets.len,
+            LengthType::SubPackets => self.operands.len(),
+        };
+        if !length_type.fits(length) {
+            return Err(EncodeError::LengthTooLarge {
+                op: self.op,
+                length_type,
+                length,
+            });
+        }
+
+        writer.write_bit(length_type.bit());
+        writer.write(length as u64, length_type.field_len());
+        writer.append(&subpackets);
+
+        Ok(())
+    }
+
+    fn write_literal(writer: &mut BitWriter, value: u64) {
+        let chunk_len = LITERAL_CHUNK_LEN - 1;
+        let significant_bits = (64 - value.leading_zeros() as usize).max(1);
+        let chunks = significant_bits.div_ceil(chunk_len);
+
+        for chunk in (0..chunks).rev() {
+            writer.write_bit(chunk > 0);
+            writer.write(value >> (chunk * chunk_len) & 0b1111, chunk_len);
+        }
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        self.version as u64
+            + self
+                .operands
+                .iter()
+                .map(|operand| operand.version_sum())
+                .sum::<u64>()
+    }
+
+    pub fn eval(&self) -> Result<u64, EvalError> {
+        let values = self
+            .operands
+            .iter()
+            .map(|operand| operand.eval())
+            .collect::<Result<Vec<u64>, EvalError>>()?;
+        if !self.op.accepts_operands(values.len()) {
+            return Err(EvalError::WrongArity {
+                op: self.op,
+                operands: values.len(),
+            });
+        }
+        let overflow = EvalError::Overflow { op: self.op };
+
+        Ok(match self.op {
+            PacketType::Literal(value) => value,
+            PacketType::Sum => values
+                .iter()
+                .try_fold(0u64, |sum, value| sum.checked_add(*value))
+                .ok_or(overflow)?,
+            PacketType::Product => values
+                .iter()
+                .try_fold(1u64, |product, value| product.checked_mul(*value))
+                .ok_or(overflow)?,
+            PacketType::Minimum => values.iter().fold(u64::MAX, |min, value| min.min(*value)),
+            PacketType::Maximum => values.iter().fold(0, |max, value| max.max(*value)),
+            PacketType::GreaterThan => (values[0] > values[1]) as u64,
+            PacketType::LessThan => (values[0] < values[1]) as u64,
+            PacketType::EqualTo => (values[0] == values[1]) as u64,
+        })
+    }
+
+    // an indented outline of the packet tree, with the metadata of every packet
+    pub fn pretty(&self) -> String {
+        let mut output = String::new();
+        self.write_pretty(&mut output, 0);
+        output
+    }
+
+    fn write_pretty(&self, output: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+
+        match (self.op, self.length_type) {
+            (PacketType::Literal(value), _) => {
+                writeln!(output, "{}v{} literal: {}", indent, self.version, value).unwrap()
+            }
+            (op, length_type) => {
+                let length_type = match length_type {
+                    Some(LengthType::Bits) => "length in bits",
+                    Some(LengthType::SubPackets) => "subpacket count",
+                    None => "no length type",
+                };
+                writeln!(
+                    output,
+                    "{}v{} {} (type {}, {})",
+                    indent,
+                    self.version,
+                    op.name(),
+                    op.type_id(),
+                    length_type
+                )
+                .unwrap();
+            }
+        }
+
+        for operand in &self.operands {
+            operand.write_pretty(output, depth + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION_SUM_EXAMPLES: [(&str, u64); 4] = [
+        ("8A004A801A8002F478", 16),
+        ("620080001611562C8802118E34", 12),
+        ("C0015000016115A2E0802F182340", 23),
+        ("A0016C880162017C3686B18A3D4780", 31),
+    ];
+
+    const EVAL_EXAMPLES: [(&str, u64); 8] = [
+        ("C200B40A82", 3),
+        ("04005AC33890", 54),
+        ("880086C3E88112", 7),
+        ("CE00C43D881120", 9),
+        ("D8005AC2A8F0", 1),
+        ("F600BC2D8F", 0),
+        ("9C005AC2F8F0", 0),
+        ("9C0141080250320F1802104A08", 1),
+    ];
+
+    #[test]
+    fn literal() {
+        assert_eq!(Packet::decode("D2FE28"), Ok(Packet::literal(6, 2021)));
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(
+            Packet::decode("38006F45291200"),
+            Ok(Packet::operator(
+                1,
+                PacketType::LessThan,
+                LengthType::Bits,
+                vec![Packet::literal(6, 10), Packet::literal(2, 20)]
+            ))
+        );
+        assert_eq!(
+            Packet::decode("EE00D40C823060"),
+            Ok(Packet::operator(
+                7,
+                PacketType::Maximum,
+                LengthType::SubPackets,
+                vec![
+                    Packet::literal(2, 1),
+                    Packet::literal(4, 2),
+                    Packet::literal(1, 3)
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn version_sums() {
+        for (hex_string, version_sum) in VERSION_SUM_EXAMPLES {
+            assert_eq!(
+                Packet::decode(hex_string).unwrap().version_sum(),
+                version_sum
+            );
+        }
+    }
+
+    #[test]
+    fn eval() {
+        for (hex_string, value) in EVAL_EXAMPLES {
+            assert_eq!(
+                Packet::decode(hex_string).unwrap().eval(),
+                Ok(value),
+                "{}",
+                hex_string
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let examples = ["D2FE28", "38006F45291200", "EE00D40C823060"]
+            .into_iter()
+            .chain(VERSION_SUM_EXAMPLES.map(|(hex_string, _)| hex_string))
+            .chain(EVAL_EXAMPLES.map(|(hex_string, _)| hex_string));
+
+        for hex_string in examples {
+            let packet = Packet::decode(hex_string).unwrap();
+            assert_eq!(packet.encode().as_deref(), Ok(hex_string));
+            assert_eq!(Packet::decode(&packet.encode().unwrap()), Ok(packet));
+        }
+    }
+
+    fn hex_from_bits(bits: &[(u64, usize)]) -> String {
+        let mut writer = BitWriter::new();
+        for (value, count) in bits {
+            writer.write(*value, *count);
+        }
+        bytes_to_hex(&writer.into_bytes())
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            Packet::decode("D2FX28"),
+            Err(DecodeError::InvalidHexDigit {
+                position: 3,
+                digit: 'X'
+            })
+        );
+        assert_eq!(
+            Packet::decode("D2FE"),
+            Err(DecodeError::Truncated { position: 16 })
+        );
+        assert_eq!(
+            Packet::decode(""),
+            Err(DecodeError::Truncated { position: 0 })
+        );
+        assert_eq!(
+            Packet::decode("D2FE29"),
+            Err(DecodeError::NonZeroPadding { position: 21 })
+        );
+    }
+
+    #[test]
+    fn empty_operators() {
+        // a sum with a length in bits of zero
+        assert_eq!(
+            Packet::decode("000000"),
+            Err(DecodeError::WrongArity {
+                position: 0,
+                op: PacketType::Sum,
+                operands: 0
+            })
+        );
+        // a minimum with a subpacket count of zero
+        assert_eq!(
+            Packet::decode(&hex_from_bits(&[(0, 3), (2, 3), (1, 1), (0, 11)])),
+            Err(DecodeError::WrongArity {
+                position: 0,
+                op: PacketType::Minimum,
+                operands: 0
+            })
+        );
+    }
+
+    #[test]
+    fn comparison_arity() {
+        let packet = Packet::operator(
+            0,
+            PacketType::LessThan,
+            LengthType::SubPackets,
+            vec![Packet::literal(0, 1); 3],
+        );
+
+        assert_eq!(
+            Packet::decode(&packet.encode().unwrap()),
+            Err(DecodeError::WrongArity {
+                position: 0,
+                op: PacketType::LessThan,
+                operands: 3
+            })
+        );
+        assert_eq!(
+            packet.eval(),
+            Err(EvalError::WrongArity {
+                op: PacketType::LessThan,
+                operands: 3
+            })
+        );
+    }
+
+    #[test]
+    fn length_mismatch() {
+        // the subpacket length is 5 bits but the literal inside it takes 11
+        let hex_string = hex_from_bits(&[(0, 3), (0, 3), (0, 1), (5, 15), (4, 6), (1, 5)]);
+
+        assert_eq!(
+            Packet::decode(&hex_string),
+            Err(DecodeError::LengthMismatch {
+                position: 0,
+                expected_end: 27,
+                actual_end: 33
+            })
+        );
+    }
+
+    #[test]
+    fn literal_too_large() {
+        let mut bits = vec![(0, 3), (4, 3)];
+        bits.extend([(0b11111, 5); 16]);
+        bits.push((0b01111, 5));
+
+        assert_eq!(
+            Packet::decode(&hex_from_bits(&bits)),
+            Err(DecodeError::LiteralTooLarge { position: 6 })
+        );
+
+        bits.remove(2);
+        assert_eq!(
+            Packet::decode(&hex_from_bits(&bits)),
+            Ok(Packet::literal(0, u64::MAX))
+        );
+    }
+
+    #[test]
+    fn nesting_limit() {
+        // a chain of sums with one subpacket each, ending in a literal 1
+        let nested = |depth: usize| {
+            let mut bits = vec![];
+            for _ in 0..depth {
+                bits.extend([(0, 3), (0, 3), (1, 1), (1, 11)]);
+            }
+            bits.extend([(0, 3), (4, 3), (1, 5)]);
+            hex_from_bits(&bits)
+        };
+
+        assert_eq!(Packet::decode(&nested(MAX_DEPTH)).unwrap().eval(), Ok(1));
+        assert_eq!(
+            Packet::decode(&nested(MAX_DEPTH + 1)),
+            Err(DecodeError::TooDeep {
+                position: (MAX_DEPTH + 1) * 18
+            })
+        );
+        // deep enough to overflow the stack without the limit
+        assert!(matches!(
+            Packet::decode(&nested(200_000)),
+            Err(DecodeError::TooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn encode_errors() {
+        assert_eq!(
+            Packet::literal(9, 1).encode(),
+            Err(EncodeError::VersionTooLarge { version: 9 })
+        );
+        assert_eq!(Packet::literal(7, 1).encode().as_deref(), Ok("F020"));
+
+        let sum = |length_type, operands| {
+            Packet::operator(
+                0,
+                PacketType::Sum,
+                length_type,
+                vec![Packet::literal(0, 1); operands],
+            )
+        };
+        assert_eq!(
+            sum(LengthType::SubPackets, 3000).encode(),
+            Err(EncodeError::LengthTooLarge {
+                op: PacketType::Sum,
+                length_type: LengthType::SubPackets,
+                length: 3000
+            })
+        );
+        // each literal 1 is 11 bits
+        assert!(sum(LengthType::Bits, 2978).encode().is_ok());
+        assert_eq!(
+            sum(LengthType::Bits, 2979).encode(),
+            Err(EncodeError::LengthTooLarge {
+                op: PacketType::Sum,
+                length_type: LengthType::Bits,
+                length: 32769
+            })
+        );
+
+        let mut packet = Packet::literal(0, 1);
+        for _ in 0..=MAX_DEPTH {
+            packet = Packet::operator(0, PacketType::Sum, LengthType::SubPackets, vec![packet]);
+        }
+        assert_eq!(packet.encode(), Err(EncodeError::TooDeep));
+        let packet = packet.operands.pop().unwrap();
+        assert_eq!(
+            Packet::decode(&packet.encode().unwrap()).unwrap().eval(),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn eval_empty_operator() {
+        let packet = Packet::operator(0, PacketType::Maximum, LengthType::Bits, vec![]);
+
+        assert_eq!(
+            packet.eval(),
+            Err(EvalError::WrongArity {
+                op: PacketType::Maximum,
+                operands: 0
+            })
+        );
+    }
+
+    #[test]
+    fn pretty() {
+        assert_eq!(
+            Packet::decode("38006F45291200").unwrap().pretty(),
+            "v1 less than (type 6, length in bits)\n  v6 literal: 10\n  v2 literal: 20\n"
+        );
+    }
+}
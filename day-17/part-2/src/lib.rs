@@ -0,0 +1,474 @@
+use regex::Regex;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+pub mod plot;
+
+pub type Velocity = (isize, isize);
+pub type Position = (isize, isize);
+
+#[derive(Debug, PartialEq)]
+pub enum LaunchResult {
+    // the highest y position reached on the way
+    Success(isize),
+    // the probe passed the side of the target area furthest from the origin
+    OvershotX,
+    // the probe is falling and already below the target area
+    OvershotY,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Target {
+    pub x_range: RangeInclusive<isize>,
+    pub y_range: RangeInclusive<isize>,
+}
+
+impl Target {
+    pub fn new(input: &str) -> Self {
+        let input_regex = Regex::new(r"x=(-?\d+)..(-?\d+), y=(-?\d+)..(-?\d+)").unwrap();
+        let captures = input_regex
+            .captures(input)
+            .expect("expected target area: x=a..b, y=c..d");
+        let bound = |i: usize| -> isize { captures[i].parse().unwrap() };
+
+        let (x1, x2) = (bound(1), bound(2));
+        let (y1, y2) = (bound(3), bound(4));
+
+        Self {
+            x_range: x1.min(x2)..=x1.max(x2),
+            y_range: y1.min(y2)..=y1.max(y2),
+        }
+    }
+
+    pub fn contains(&self, (x, y): Position) -> bool {
+        self.x_range.contains(&x) && self.y_range.contains(&y)
+    }
+}
+
+pub struct Probe {
+    pub x: isize,
+    pub y: isize,
+    pub x_velocity: isize,
+    pub y_velocity: isize,
+    // every position so far, starting with the origin
+    pub positions: Vec<Position>,
+}
+
+impl Probe {
+    pub fn new(x_velocity: isize, y_velocity: isize) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            x_velocity,
+            y_velocity,
+            positions: vec![(0, 0)],
+        }
+    }
+
+    pub fn launch(&mut self, target: &Target) -> LaunchResult {
+        let (x_start, x_end) = (*target.x_range.start(), *target.x_range.end());
+        let mut max_y = self.y;
+        loop {
+            self.step();
+            if self.y > max_y {
+                max_y = self.y;
+            }
+            // the probe only ever moves away from the origin horizontally, so once it's past the
+            // far side of the target area it can't come back
+            if target.contains((self.x, self.y)) {
+                return LaunchResult::Success(max_y);
+            } else if (self.x > x_end && self.x > 0) || (self.x < x_start && self.x < 0) {
+                return LaunchResult::OvershotX;
+            } else if self.y < *target.y_range.start() && self.y_velocity < 0 {
+                return LaunchResult::OvershotY;
+            }
+        }
+    }
+
+    pub fn step(&mut self) {
+        self.x += self.x_velocity;
+        self.y += self.y_velocity;
+
+        self.x_velocity -= self.x_velocity.signum();
+        self.y_velocity -= 1;
+
+        self.positions.push((self.x, self.y));
+    }
+}
+
+// The steps during which the probe is inside the target area along one axis. The x position
+// stops changing once drag has brought the x velocity to zero, so x windows can be open ended.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct StepWindow {
+    pub first: isize,
+    pub last: Option<isize>,
+}
+
+impl StepWindow {
+    // the first step in both windows, if there is one
+    pub fn first_common_step(&self, other: &StepWindow) -> Option<isize> {
+        let first = self.first.max(other.first);
+        let last = match (self.last, other.last) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(last), None) | (None, Some(last)) => last,
+            (None, None) => return Some(first),
+        };
+
+        (first <= last).then_some(first)
+    }
+}
+
+// the position after n steps of an axis with no drag, starting from the origin
+fn position(velocity: isize, n: isize) -> isize {
+    n * velocity - n * (n - 1) / 2
+}
+
+// The real range of n where position(velocity, n) >= bound, rounded inwards to whole numbers.
+// The position is a downward parabola in n, so this is the span between the roots of
+// n^2 - (2v + 1)n + 2 * bound = 0.
+fn steps_at_or_above(velocity: isize, bound: isize) -> Option<(isize, isize)> {
+    let b = 2 * velocity + 1;
+    let discriminant = b * b - 8 * bound;
+    if discriminant < 0 {
+        return None;
+    }
+
+    let root = (discriminant as f64).sqrt();
+    let mut first = ((b as f64 - root) / 2.0).ceil() as isize;
+    let mut last = ((b as f64 + root) / 2.0).floor() as isize;
+
+    // correct for any floating point error in the square root
+    while position(velocity, first - 1) >= bound {
+        first -= 1;
+    }
+    while position(velocity, first) < bound && first <= last {
+        first += 1;
+    }
+    while position(velocity, last + 1) >= bound {
+        last += 1;
+    }
+    while position(velocity, last) < bound && last >= first {
+        last -= 1;
+    }
+
+    (first <= last).then_some((first, last))
+}
+
+// The steps (counting from 1) where position(velocity, n) lies in `range`, limited to at most
+// `max_steps` steps. The parabola passes through the range at most twice, once on the way up and
+// once on the way down.
+fn parabola_windows(
+    velocity: isize,
+    range: &RangeInclusive<isize>,
+    max_steps: Option<isize>,
+) -> Vec<(isize, isize)> {
+    let (start, end) = (*range.start(), *range.end());
+    let Some((first, last)) = steps_at_or_above(velocity, start) else {
+        return vec![];
+    };
+    let first = first.max(1);
+    let last = max_steps.map_or(last, |max_steps| last.min(max_steps));
+
+    let candidates = match steps_at_or_above(velocity, end + 1) {
+        Some((above_first, above_last)) => {
+            vec![
+                (first, last.min(above_first - 1)),
+                (first.max(above_last + 1), last),
+            ]
+        }
+        None => vec![(first, last)],
+    };
+
+    candidates
+        .into_iter()
+        .filter(|(first, last)| first <= last)
+        .collect()
+}
+
+pub fn x_step_window(x_velocity: isize, x_range: &RangeInclusive<isize>) -> Option<StepWindow> {
+    // mirror targets to the left of the origin so the velocity is never negative
+    let (velocity, range) = match x_velocity < 0 {
+        true => (-x_velocity, -*x_range.end()..=-*x_range.start()),
+        false => (x_velocity, x_range.clone()),
+    };
+
+    // the probe stops moving horizontally after `velocity` steps
+    let resting_position = position(velocity, velocity);
+    if velocity == 0 || range.contains(&resting_position) {
+        let first = match velocity {
+            0 => 1,
+            _ => parabola_windows(velocity, &range, Some(velocity))
+                .first()
+                .map_or(velocity.max(1), |(first, _)| *first),
+        };
+        return range
+            .contains(&resting_position)
+            .then_some(StepWindow { first, last: None });
+    }
+
+    parabola_windows(velocity, &range, Some(velocity))
+        .first()
+        .map(|(first, last)| StepWindow {
+            first: *first,
+            last: Some(*last),
+        })
+}
+
+pub fn y_step_windows(y_velocity: isize, y_range: &RangeInclusive<isize>) -> Vec<StepWindow> {
+    parabola_windows(y_velocity, y_range, None)
+        .into_iter()
+        .map(|(first, last)| StepWindow {
+            first,
+            last: Some(last),
+        })
+        .collect()
+}
+
+// The highest point of the trajectory up to and including the given step, counting the starting
+// position. The probe is at its peak after y_velocity steps.
+pub fn max_height(y_velocity: isize, steps: isize) -> isize {
+    match y_velocity > 0 {
+        true => position(y_velocity, y_velocity.min(steps)),
+        false => 0,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Solution {
+    // the highest point reached before first entering the target area, None if there are no valid
+    // velocities at all
+    pub max_height: Option<isize>,
+    // every velocity that puts the probe in the target area after some step, sorted
+    pub velocities: Vec<Velocity>,
+}
+
+impl Solution {
+    pub fn count(&self) -> usize {
+        self.velocities.len()
+    }
+}
+
+// Every upwards launch comes back down through y=0 after 2 * y_velocity + 1 steps, so when the
+// target area's y range includes 0 and the probe can come to rest horizontally inside its x range,
+// any y velocity that's high enough works.
+#[derive(Debug, PartialEq)]
+pub struct Unbounded {
+    pub x_velocity: isize,
+}
+
+impl fmt::Display for Unbounded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "every high enough launch with an x velocity of {} hits the target area",
+            self.x_velocity
+        )
+    }
+}
+
+impl Error for Unbounded {}
+
+// Finds every initial velocity that puts the probe inside the target area after some whole number
+// of steps, without simulating any launches. The x and y positions are independent, so each axis
+// has its own windows of steps during which it's within the target range, and a velocity works
+// if its x window overlaps one of its y windows.
+pub fn solve(target: &Target) -> Result<Solution, Unbounded> {
+    let (x_start, x_end) = (*target.x_range.start(), *target.x_range.end());
+    let (y_start, y_end) = (*target.y_range.start(), *target.y_range.end());
+
+    // any faster and the first step already jumps past the target area
+    let x_velocities = x_start.min(0)..=x_end.max(0);
+    let x_windows: Vec<(isize, StepWindow)> = x_velocities
+        .filter_map(|x_velocity| {
+            x_step_window(x_velocity, &target.x_range).map(|window| (x_velocity, window))
+        })
+        .collect();
+
+    // Launching upwards any faster, every position above the origin is above the target area and
+    // the first one below it is below the target area. That leaves y=0 itself, which is reached
+    // after 2 * y_velocity + 1 steps, but unless the x window is open ended it's over by the time
+    // the probe has slowed to a stop after at most x_velocity steps.
+    let mut max_y_velocity = y_start.abs().max(y_end.abs());
+    if target.y_range.contains(&0) {
+        if let Some((x_velocity, _)) = x_windows.iter().find(|(_, window)| window.last.is_none()) {
+            return Err(Unbounded {
+                x_velocity: *x_velocity,
+            });
+        }
+        max_y_velocity = max_y_velocity.max(x_start.abs().max(x_end.abs()));
+    }
+    let y_velocities = y_start.min(0)..=max_y_velocity;
+
+    let mut velocities = vec![];
+    let mut heights = vec![];
+    for y_velocity in y_velocities {
+        let y_windows = y_step_windows(y_velocity, &target.y_range);
+        for (x_velocity, x_window) in &x_windows {
+            let first_step = y_windows
+                .iter()
+                .filter_map(|y_window| y_window.first_common_step(x_window))
+                .min();
+            if let Some(first_step) = first_step {
+                velocities.push((*x_velocity, y_velocity));
+                heights.push(max_height(y_velocity, first_step));
+            }
+        }
+    }
+    velocities.sort();
+
+    Ok(Solution {
+        max_height: heights.into_iter().max(),
+        velocities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "target area: x=20..30, y=-10..-5";
+
+    fn simulate(target: &Target, limit: isize) -> Vec<Velocity> {
+        let mut velocities = vec![];
+        for x_velocity in -limit..=limit {
+            for y_velocity in -limit..=limit {
+                let mut probe = Probe::new(x_velocity, y_velocity);
+                if let LaunchResult::Success(_) = probe.launch(target) {
+                    velocities.push((x_velocity, y_velocity));
+                }
+            }
+        }
+        velocities
+    }
+
+    #[test]
+    fn positions() {
+        let target = Target::new(TEST_INPUT);
+        let mut probe = Probe::new(7, 2);
+
+        assert_eq!(probe.launch(&target), LaunchResult::Success(3));
+        assert_eq!(
+            probe.positions,
+            vec![
+                (0, 0),
+                (7, 2),
+                (13, 3),
+                (18, 3),
+                (22, 2),
+                (25, 0),
+                (27, -3),
+                (28, -7)
+            ]
+        );
+    }
+
+    #[test]
+    fn example() {
+        let target = Target::new(TEST_INPUT);
+        let solution = solve(&target).unwrap();
+
+        assert_eq!(solution.max_height, Some(45));
+        assert_eq!(solution.count(), 112);
+        assert!(solution.velocities.contains(&(6, 9)));
+        assert!(solution.velocities.contains(&(7, -1)));
+        assert!(!solution.velocities.contains(&(17, -4)));
+    }
+
+    #[test]
+    fn step_windows() {
+        let target = Target::new(TEST_INPUT);
+
+        // comes to rest at x=21
+        assert_eq!(
+            x_step_window(6, &target.x_range),
+            Some(StepWindow {
+                first: 5,
+                last: None
+            })
+        );
+        assert_eq!(
+            x_step_window(30, &target.x_range),
+            Some(StepWindow {
+                first: 1,
+                last: Some(1)
+            })
+        );
+        assert_eq!(x_step_window(17, &target.x_range), None);
+        // reaches y=0 again after 19 steps then drops to -10 at step 20
+        assert_eq!(
+            y_step_windows(9, &target.y_range),
+            vec![StepWindow {
+                first: 20,
+                last: Some(20)
+            }]
+        );
+    }
+
+    #[test]
+    fn target_above_has_two_y_windows() {
+        // passes through 7, 9 on the way up and 9, 7 on the way down
+        assert_eq!(
+            y_step_windows(4, &(7..=9)),
+            vec![
+                StepWindow {
+                    first: 2,
+                    last: Some(3)
+                },
+                StepWindow {
+                    first: 6,
+                    last: Some(7)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn unbounded() {
+        // comes to rest at x=21, then any upwards launch falls back through y=0
+        assert_eq!(
+            solve(&Target::new("target area: x=20..30, y=-4..6")),
+            Err(Unbounded { x_velocity: 6 })
+        );
+        assert_eq!(
+            solve(&Target::new("target area: x=-3..3, y=-3..3")),
+            Err(Unbounded { x_velocity: -2 })
+        );
+    }
+
+    #[test]
+    fn matches_simulation() {
+        let targets = [
+            TEST_INPUT,
+            "target area: x=-30..-20, y=-10..-5",
+            "target area: x=20..30, y=5..10",
+            "target area: x=-30..-20, y=5..10",
+            "target area: x=-5..8, y=-10..-5",
+            "target area: x=40..44, y=-4..6",
+            "target area: x=-44..-40, y=-2..3",
+            "target area: x=0..0, y=-2..-2",
+        ];
+
+        for input in targets {
+            let target = Target::new(input);
+            let solution = solve(&target).unwrap();
+
+            assert_eq!(solution.velocities, simulate(&target, 50), "{}", input);
+            assert_eq!(
+                solution.max_height,
+                solution
+                    .velocities
+                    .iter()
+                    .map(|(x_velocity, y_velocity)| {
+                        match Probe::new(*x_velocity, *y_velocity).launch(&target) {
+                            LaunchResult::Success(max_y) => max_y,
+                            _ => unreachable!(),
+                        }
+                    })
+                    .max(),
+                "{}",
+                input
+            );
+        }
+    }
+}
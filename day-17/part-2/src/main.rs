@@ -1,123 +1,86 @@
 // Advent of Code 2021: Day 17, Part 2
 // https://adventofcode.com/2021/day/17
-// Usage `cargo run <input-file>
+// Usage `cargo run <input-file> [--list] [--plot <x>,<y>] [--plot-all] [--svg <file>]`
+//
+// --list prints every initial velocity that hits the target area
+// --plot draws the trajectory for one initial velocity, successful or not, like the puzzle text
+// --plot-all draws every successful trajectory together instead
+// --svg saves the plotted trajectories as an svg rather than printing them
+//
+// Rather than trying launches, this works out for each initial x and y velocity which steps the
+// probe spends inside the target area along that axis, then pairs up the velocities whose steps
+// overlap. That works wherever the target area is relative to the origin.
+
+use part_2::plot::{render_ascii, to_svg};
+use part_2::{solve, Probe, Target, Velocity};
+use std::{env, fs, process};
 
-use regex::Regex;
-use std::{env, fs, ops::RangeInclusive};
-
-enum LaunchResult {
-    Success(isize),
-    OvershotX,
-    OvershotY,
-}
-
-struct Target {
-    x_range: RangeInclusive<isize>,
-    y_range: RangeInclusive<isize>,
-}
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let input_file = args.get(1).expect("please supply an input file");
+    let list = args.iter().any(|arg| arg == "--list");
+    let plot_all = args.iter().any(|arg| arg == "--plot-all");
+    let option_value = |option: &str| {
+        args.iter().position(|arg| arg == option).map(|i| {
+            args.get(i + 1)
+                .unwrap_or_else(|| panic!("please supply a value for {}", option))
+        })
+    };
+    let plot: Option<Velocity> = option_value("--plot").map(|velocity| {
+        let (x, y) = velocity
+            .split_once(',')
+            .expect("expected a velocity like 6,9");
+        (
+            x.parse().expect("invalid x velocity"),
+            y.parse().expect("invalid y velocity"),
+        )
+    });
+    let svg_file = option_value("--svg");
+    let input = fs::read_to_string(input_file).unwrap();
 
-struct Probe {
-    x: isize,
-    y: isize,
-    x_velocity: isize,
-    y_velocity: isize,
-}
+    let target = Target::new(&input);
+    let solution = solve(&target).unwrap_or_else(|error| {
+        eprintln!("There are infinitely many trajectories: {}", error);
+        process::exit(1);
+    });
 
-impl Probe {
-    fn new(x_velocity: isize, y_velocity: isize) -> Self {
-        Self {
-            x: 0,
-            y: 0,
-            x_velocity,
-            y_velocity,
+    if list {
+        for (x_velocity, y_velocity) in &solution.velocities {
+            println!("{},{}", x_velocity, y_velocity);
         }
     }
 
-    fn launch(&mut self, target: &Target) -> LaunchResult {
-        let mut max_y = self.y;
-        loop {
-            self.step();
-            if self.y > max_y {
-                max_y = self.y;
-            }
-            if target.x_range.contains(&self.x) && target.y_range.contains(&self.y) {
-                return LaunchResult::Success(max_y);
-            } else if self.x > *target.x_range.end() {
-                return LaunchResult::OvershotX;
-            } else if self.y < *target.y_range.start() {
-                return LaunchResult::OvershotY;
-            }
+    let velocities = match plot {
+        Some(velocity) => vec![velocity],
+        None if plot_all => solution.velocities.clone(),
+        None => vec![],
+    };
+    if !velocities.is_empty() {
+        let trajectories: Vec<_> = velocities
+            .iter()
+            .map(|(x_velocity, y_velocity)| {
+                let mut probe = Probe::new(*x_velocity, *y_velocity);
+                let result = probe.launch(&target);
+                if plot.is_some() {
+                    println!("{},{}: {:?}", x_velocity, y_velocity, result);
+                }
+                probe.positions
+            })
+            .collect();
+
+        match svg_file {
+            Some(svg_file) => fs::write(svg_file, to_svg(&target, &trajectories, 10))
+                .expect("could not write the svg"),
+            None => print!("{}", render_ascii(&target, &trajectories)),
         }
     }
 
-    fn step(&mut self) {
-        self.x += self.x_velocity;
-        self.y += self.y_velocity;
-
-        self.x_velocity += if self.x_velocity > 0 {
-            -1
-        } else if self.x_velocity < 0 {
-            1
-        } else {
-            0
-        };
-
-        self.y_velocity -= 1;
+    match solution.max_height {
+        Some(max_height) => println!("The max y reached by any trajectory is {}", max_height),
+        None => println!("No trajectory reaches the target area"),
     }
-}
-
-// I think the minimum viable initial x velocity is x such that the sum of the first x whole
-// numbers is the lowest one in the range of the target zone. This is also the one I feel is most
-// likely to produce the optimal height as it will be farthest to the left in the target zone. I'm
-// not 100% confident on this, but it worked at least for the sample input.
-fn minimum_x(x_start: isize, x_end: isize) -> isize {
-    let mut x: isize = 0;
-
-    while x < x_start {
-        let sum_of_first_n = (x + 1) * x / 2;
-        if sum_of_first_n >= x_start && sum_of_first_n <= x_end {
-            return x;
-        }
-        x += 1;
-    }
-    x
-}
-
-fn main() {
-    let input_file = env::args().nth(1).expect("please supply an input file");
-    let input = fs::read_to_string(input_file).unwrap();
-
-    let input_regex = Regex::new(r"x=(-?\d+)..(-?\d+), y=(-?\d+)..(-?\d+)").unwrap();
-    let captures = input_regex.captures(&input).unwrap();
-
-    let (x1, x2): (isize, isize) = (
-        captures.get(1).unwrap().as_str().parse().unwrap(),
-        captures.get(2).unwrap().as_str().parse().unwrap(),
+    println!(
+        "The number of possible trajectories are {}",
+        solution.count()
     );
-    let (y1, y2): (isize, isize) = (
-        captures.get(3).unwrap().as_str().parse().unwrap(),
-        captures.get(4).unwrap().as_str().parse().unwrap(),
-    );
-
-    let target = Target {
-        x_range: x1..=x2,
-        y_range: y1..=y2,
-    };
-
-    let mut successes = 0;
-    for x_velocity in minimum_x(x1, x2)..=x2 {
-        // This guess for the range of possible initial y velocities is based on some observations
-        // about the positions of the steps. For any given y launched upward, it always passes
-        // through the origin on its way down. If the next step is greater than the distance from
-        // the origin to the bottom of the target area, then we will overshoot the target, so we
-        // don't need to consider values larger than that.
-        for y_velocity in y1..=(y1.abs()) {
-            let mut probe = Probe::new(x_velocity, y_velocity);
-            if let LaunchResult::Success(_) = probe.launch(&target) {
-                successes += 1;
-            }
-        }
-    }
-
-    println!("The number of possible trajectories are {}", successes);
 }
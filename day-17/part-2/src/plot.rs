@@ -0,0 +1,202 @@
+use crate::{Position, Target};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+// the area covering the origin, the target area and every position of every trajectory
+fn bounds(target: &Target, trajectories: &[Vec<Position>]) -> (Position, Position) {
+    let corners = [
+        (0, 0),
+        (*target.x_range.start(), *target.y_range.start()),
+        (*target.x_range.end(), *target.y_range.end()),
+    ];
+    let points = corners.iter().chain(trajectories.iter().flatten());
+
+    let min_x = points.clone().map(|(x, _)| *x).min().unwrap();
+    let max_x = points.clone().map(|(x, _)| *x).max().unwrap();
+    let min_y = points.clone().map(|(_, y)| *y).min().unwrap();
+    let max_y = points.map(|(_, y)| *y).max().unwrap();
+
+    ((min_x, min_y), (max_x, max_y))
+}
+
+// Draws the trajectories like the puzzle text does, with the origin as S, the target area as T
+// and every position a probe was in as #, with up being positive y.
+pub fn render_ascii(target: &Target, trajectories: &[Vec<Position>]) -> String {
+    let ((min_x, min_y), (max_x, max_y)) = bounds(target, trajectories);
+    let positions: HashSet<Position> = trajectories.iter().flatten().copied().collect();
+    let mut output = String::new();
+
+    for y in (min_y..=max_y).rev() {
+        for x in min_x..=max_x {
+            let c = if (x, y) == (0, 0) {
+                'S'
+            } else if positions.contains(&(x, y)) {
+                '#'
+            } else if target.contains((x, y)) {
+                'T'
+            } else {
+                '.'
+            };
+            output.push(c);
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+// Draws the target area and the trajectories as an SVG with `scale` pixels per unit, giving each
+// trajectory its own color and marking each of its positions with a dot.
+pub fn to_svg(target: &Target, trajectories: &[Vec<Position>], scale: usize) -> String {
+    let ((min_x, min_y), (max_x, max_y)) = bounds(target, trajectories);
+    let scale = scale as isize;
+    // one unit of margin on every side, with y flipped so up is positive
+    let to_svg_x = |x: isize| (x - min_x + 1) * scale;
+    let to_svg_y = |y: isize| (max_y - y + 1) * scale;
+    let (width, height) = ((max_x - min_x + 2) * scale, (max_y - min_y + 2) * scale);
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        width, height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<rect x="0" y="0" width="{}" height="{}" fill="white"/>"#,
+        width, height
+    )
+    .unwrap();
+
+    // the target area covers whole cells around each position, like the Ts in the ascii plot
+    let (x_start, x_end) = (*target.x_range.start(), *target.x_range.end());
+    let (y_start, y_end) = (*target.y_range.start(), *target.y_range.end());
+    writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#9ecae1" stroke="#3182bd"/>"##,
+        to_svg_x(x_start) - scale / 2,
+        to_svg_y(y_end) - scale / 2,
+        (x_end - x_start + 1) * scale,
+        (y_end - y_start + 1) * scale
+    )
+    .unwrap();
+
+    let radius = (scale / 4).max(1);
+    for (i, trajectory) in trajectories.iter().enumerate() {
+        // spread the hues of the trajectories evenly around the color wheel
+        let hue = i * 360 / trajectories.len();
+        let points: Vec<String> = trajectory
+            .iter()
+            .map(|(x, y)| format!("{},{}", to_svg_x(*x), to_svg_y(*y)))
+            .collect();
+
+        writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="hsl({}, 70%, 45%)" stroke-width="{}"/>"#,
+            points.join(" "),
+            hue,
+            (scale / 8).max(1)
+        )
+        .unwrap();
+        for (x, y) in trajectory {
+            writeln!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="hsl({}, 70%, 45%)"/>"#,
+                to_svg_x(*x),
+                to_svg_y(*y),
+                radius,
+                hue
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(
+        svg,
+        r#"<circle cx="{}" cy="{}" r="{}" fill="black"/>"#,
+        to_svg_x(0),
+        to_svg_y(0),
+        radius * 2
+    )
+    .unwrap();
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LaunchResult, Probe};
+
+    const TEST_INPUT: &str = "target area: x=20..30, y=-10..-5";
+
+    fn trajectory(target: &Target, velocity: (isize, isize)) -> (LaunchResult, Vec<Position>) {
+        let mut probe = Probe::new(velocity.0, velocity.1);
+        let result = probe.launch(target);
+        (result, probe.positions)
+    }
+
+    #[test]
+    fn ascii_matches_puzzle() {
+        let target = Target::new(TEST_INPUT);
+        let (_, positions) = trajectory(&target, (7, 2));
+
+        let expected = "\
+.............#....#............
+.......#..............#........
+...............................
+S........................#.....
+...............................
+...............................
+...........................#...
+...............................
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTT#TT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+";
+        assert_eq!(render_ascii(&target, &[positions]), expected);
+    }
+
+    #[test]
+    fn ascii_overshoot() {
+        let target = Target::new(TEST_INPUT);
+        let (result, positions) = trajectory(&target, (17, -4));
+
+        assert_eq!(result, LaunchResult::OvershotX);
+
+        let expected = "\
+S.................................
+..................................
+..................................
+..................................
+.................#................
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT...
+....................TTTTTTTTTTT..#
+....................TTTTTTTTTTT...
+";
+        assert_eq!(render_ascii(&target, &[positions]), expected);
+    }
+
+    #[test]
+    fn svg() {
+        let target = Target::new(TEST_INPUT);
+        let trajectories = vec![trajectory(&target, (7, 2)).1, trajectory(&target, (6, 3)).1];
+        let svg = to_svg(&target, &trajectories, 10);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline ").count(), 2);
+        assert!(svg.contains("hsl(0, 70%, 45%)"));
+        assert!(svg.contains("hsl(180, 70%, 45%)"));
+        // the origin marker, then every position of each trajectory including its start
+        assert_eq!(svg.matches("<circle ").count(), 1 + 8 + 10);
+    }
+}
@@ -0,0 +1,671 @@
+pub mod pairs;
+
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+
+// pairs nested inside this many pairs explode
+const EXPLODE_DEPTH: usize = 4;
+// regular numbers at least this big split
+const SPLIT_THRESHOLD: u32 = 10;
+// how many pairs deep a parsed number can be, so parsing doesn't run out of stack
+pub const MAX_DEPTH: usize = 256;
+
+// Snailfish numbers are immutable trees. Reducing one builds a new tree rather than changing it in
+// place, so the magnitude can be taken without losing the number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnailfishNumber {
+    Regular(u32),
+    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+}
+
+use SnailfishNumber::{Pair, Regular};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Action {
+    Explode,
+    Split,
+}
+
+// one action taken while reducing a number, with the number as it was straight afterwards
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReductionStep {
+    pub action: Action,
+    pub number: SnailfishNumber,
+}
+
+// formatted like the worked examples in the puzzle, e.g. "after explode:  [[0,7],4]"
+impl fmt::Display for ReductionStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.action {
+            Action::Explode => write!(f, "after explode:  {}", self.number),
+            Action::Split => write!(f, "after split:    {}", self.number),
+        }
+    }
+}
+
+// Exploding a pair adds its regular numbers to their neighbours, which can make one too big for a
+// u32 if the numbers being added are big enough already.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RegularOverflow;
+
+impl fmt::Display for RegularOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "exploding a pair made a regular number too big for a u32"
+        )
+    }
+}
+
+impl Error for RegularOverflow {}
+
+// Iterates over the steps of reducing a number, one explode or split at a time. Nothing is
+// recorded unless this is used, so plain addition doesn't pay for the trace. An overflow is the
+// last item.
+pub struct Reduction {
+    // None once an overflow has been returned
+    number: Option<SnailfishNumber>,
+}
+
+impl Iterator for Reduction {
+    type Item = Result<ReductionStep, RegularOverflow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.number.as_ref()?.reduce_once() {
+            Ok(Some((action, number))) => {
+                self.number = Some(number.clone());
+                Some(Ok(ReductionStep { action, number }))
+            }
+            Ok(None) => None,
+            Err(overflow) => {
+                self.number = None;
+                Some(Err(overflow))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    UnexpectedEnd,
+    UnexpectedCharacter {
+        position: usize,
+        found: char,
+        expected: &'static str,
+    },
+    RegularTooLarge {
+        position: usize,
+    },
+    // the pair is inside MAX_DEPTH other pairs already
+    TooDeep {
+        position: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "the number ended unexpectedly"),
+            ParseError::UnexpectedCharacter {
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "column {}: expected {} but found {:?}",
+                position + 1,
+                expected,
+                found
+            ),
+            ParseError::RegularTooLarge { position } => {
+                write!(
+                    f,
+                    "column {}: the number doesn't fit in a u32",
+                    position + 1
+                )
+            }
+            ParseError::TooDeep { position } => write!(
+                f,
+                "column {}: pairs can't be nested more than {} deep",
+                position + 1,
+                MAX_DEPTH
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    // how many pairs the next number is inside
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn expect(&mut self, expected: char, description: &'static str) -> Result<(), ParseError> {
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((position, found)) => Err(ParseError::UnexpectedCharacter {
+                position,
+                found,
+                expected: description,
+            }),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<SnailfishNumber, ParseError> {
+        match self.chars.peek().copied() {
+            Some((position, '[')) => {
+                if self.depth >= MAX_DEPTH {
+                    return Err(ParseError::TooDeep { position });
+                }
+                self.chars.next();
+                self.depth += 1;
+                let left = self.parse_number()?;
+                self.expect(',', "','")?;
+                let right = self.parse_number()?;
+                self.expect(']', "']'")?;
+                self.depth -= 1;
+                Ok(Pair(Box::new(left), Box::new(right)))
+            }
+            Some((position, c)) if c.is_ascii_digit() => {
+                let mut value: u32 = 0;
+                while let Some((_, c)) = self.chars.peek().copied() {
+                    let Some(digit) = c.to_digit(10) else {
+                        break;
+                    };
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit))
+                        .ok_or(ParseError::RegularTooLarge { position })?;
+                    self.chars.next();
+                }
+                Ok(Regular(value))
+            }
+            Some((position, found)) => Err(ParseError::UnexpectedCharacter {
+                position,
+                found,
+                expected: "'[' or a digit",
+            }),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.trim().char_indices().peekable(),
+            depth: 0,
+        };
+        let number = parser.parse_number()?;
+
+        match parser.chars.next() {
+            Some((position, found)) => Err(ParseError::UnexpectedCharacter {
+                position,
+                found,
+                expected: "the end of the number",
+            }),
+            None => Ok(number),
+        }
+    }
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regular(value) => write!(f, "{}", value),
+            Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+impl SnailfishNumber {
+    pub fn pair(left: SnailfishNumber, right: SnailfishNumber) -> Self {
+        Pair(Box::new(left), Box::new(right))
+    }
+
+    // None if it doesn't fit in a u64, which only happens to numbers nested far deeper than
+    // reduced ones
+    pub fn magnitude(&self) -> Option<u64> {
+        match self {
+            Regular(value) => Some(*value as u64),
+            Pair(left, right) => left
+                .magnitude()?
+                .checked_mul(3)?
+                .checked_add(right.magnitude()?.checked_mul(2)?),
+        }
+    }
+
+    // a reduced number has no pairs that would explode and no regular numbers that would split
+    pub fn is_reduced(&self) -> bool {
+        self.is_reduced_at(0)
+    }
+
+    fn is_reduced_at(&self, depth: usize) -> bool {
+        match self {
+            Regular(value) => *value < SPLIT_THRESHOLD,
+            Pair(left, right) => {
+                depth < EXPLODE_DEPTH
+                    && left.is_reduced_at(depth + 1)
+                    && right.is_reduced_at(depth + 1)
+            }
+        }
+    }
+
+    // Repeatedly explodes the leftmost pair nested inside four pairs or, if there isn't one,
+    // splits the leftmost regular number of 10 or more, until neither applies.
+    pub fn reduce(&self) -> Result<SnailfishNumber, RegularOverflow> {
+        let mut number = self.clone();
+        while let Some((_, next)) = number.reduce_once()? {
+            number = next;
+        }
+        Ok(number)
+    }
+
+    fn reduce_once(&self) -> Result<Option<(Action, SnailfishNumber)>, RegularOverflow> {
+        if let Some(number) = self.explode()? {
+            return Ok(Some((Action::Explode, number)));
+        }
+        Ok(self.split().map(|number| (Action::Split, number)))
+    }
+
+    pub fn reduction_steps(&self) -> Reduction {
+        Reduction {
+            number: Some(self.clone()),
+        }
+    }
+
+    // the reduced sum of the two numbers, or an error if a regular number overflows
+    pub fn checked_add(&self, other: &SnailfishNumber) -> Result<SnailfishNumber, RegularOverflow> {
+        Self::pair(self.clone(), other.clone()).reduce()
+    }
+
+    // adds up the numbers in order, or returns None if there aren't any
+    pub fn checked_sum<'a>(
+        numbers: impl IntoIterator<Item = &'a SnailfishNumber>,
+    ) -> Result<Option<SnailfishNumber>, RegularOverflow> {
+        let mut numbers = numbers.into_iter();
+        let Some(first) = numbers.next() else {
+            return Ok(None);
+        };
+
+        numbers
+            .try_fold(first.clone(), |sum, number| sum.checked_add(number))
+            .map(Some)
+    }
+
+    // the unreduced pair of two numbers, whose reduction steps are the trace of adding them
+    pub fn addition_trace(&self, other: &SnailfishNumber) -> Reduction {
+        Self::pair(self.clone(), other.clone()).reduction_steps()
+    }
+
+    // the number with its leftmost deep enough pair exploded, or None if there isn't one
+    pub fn explode(&self) -> Result<Option<SnailfishNumber>, RegularOverflow> {
+        Ok(self.explode_at(0)?.map(|(number, _, _)| number))
+    }
+
+    // Returns the number with the leftmost deep enough pair replaced by 0, along with the parts
+    // of the pair that still need to be added to the regular numbers to its left and right.
+    #[allow(clippy::type_complexity)]
+    fn explode_at(
+        &self,
+        depth: usize,
+    ) -> Result<Option<(SnailfishNumber, Option<u32>, Option<u32>)>, RegularOverflow> {
+        let Pair(left, right) = self else {
+            return Ok(None);
+        };
+
+        if depth >= EXPLODE_DEPTH {
+            if let (Regular(left), Regular(right)) = (left.as_ref(), right.as_ref()) {
+                return Ok(Some((Regular(0), Some(*left), Some(*right))));
+            }
+        }
+
+        if let Some((left, to_left, to_right)) = left.explode_at(depth + 1)? {
+            let right = match to_right {
+                Some(value) => right.add_to_leftmost(value)?,
+                None => right.as_ref().clone(),
+            };
+            return Ok(Some((Self::pair(left, right), to_left, None)));
+        }
+
+        if let Some((right, to_left, to_right)) = right.explode_at(depth + 1)? {
+            let left = match to_left {
+                Some(value) => left.add_to_rightmost(value)?,
+                None => left.as_ref().clone(),
+            };
+            return Ok(Some((Self::pair(left, right), None, to_right)));
+        }
+
+        Ok(None)
+    }
+
+    fn add_to_leftmost(&self, value: u32) -> Result<SnailfishNumber, RegularOverflow> {
+        Ok(match self {
+            Regular(regular) => Regular(regular.checked_add(value).ok_or(RegularOverflow)?),
+            Pair(left, right) => Self::pair(left.add_to_leftmost(value)?, right.as_ref().clone()),
+        })
+    }
+
+    fn add_to_rightmost(&self, value: u32) -> Result<SnailfishNumber, RegularOverflow> {
+        Ok(match self {
+            Regular(regular) => Regular(regular.checked_add(value).ok_or(RegularOverflow)?),
+            Pair(left, right) => Self::pair(left.as_ref().clone(), right.add_to_rightmost(value)?),
+        })
+    }
+
+    pub fn split(&self) -> Option<SnailfishNumber> {
+        match self {
+            Regular(value) if *value >= SPLIT_THRESHOLD => {
+                Some(Self::pair(Regular(value / 2), Regular(value - value / 2)))
+            }
+            Regular(_) => None,
+            Pair(left, right) => {
+                if let Some(left) = left.split() {
+                    return Some(Self::pair(left, right.as_ref().clone()));
+                }
+                right
+                    .split()
+                    .map(|right| Self::pair(left.as_ref().clone(), right))
+            }
+        }
+    }
+}
+
+// Like integer addition, these panic if the result overflows. checked_add and checked_sum return
+// the error instead.
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, other: SnailfishNumber) -> SnailfishNumber {
+        Self::pair(self, other)
+            .reduce()
+            .expect("snailfish addition overflowed")
+    }
+}
+
+impl Add for &SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, other: &SnailfishNumber) -> SnailfishNumber {
+        self.clone() + other.clone()
+    }
+}
+
+// There's no snailfish zero, so summing no numbers at all panics.
+impl Sum for SnailfishNumber {
+    fn sum<I: Iterator<Item = SnailfishNumber>>(iter: I) -> Self {
+        iter.reduce(|sum, number| sum + number)
+            .expect("can't sum an empty list of snailfish numbers")
+    }
+}
+
+impl<'a> Sum<&'a SnailfishNumber> for SnailfishNumber {
+    fn sum<I: Iterator<Item = &'a SnailfishNumber>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const TEST_INPUT: &str = "
+        [[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+        [[[5,[2,8]],4],[5,[[9,9],0]]]
+        [6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+        [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+        [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+        [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+        [[[[5,4],[7,7]],8],[[8,3],8]]
+        [[9,3],[[9,9],[6,[4,9]]]]
+        [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+        [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+    ";
+
+    fn parse(s: &str) -> SnailfishNumber {
+        s.parse().unwrap()
+    }
+
+    fn parse_all(input: &str) -> Vec<SnailfishNumber> {
+        input.trim().lines().map(parse).collect()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        for line in TEST_INPUT.trim().lines() {
+            assert_eq!(parse(line).to_string(), line.trim());
+        }
+        assert_eq!(
+            parse("[123,[4,56]]"),
+            SnailfishNumber::pair(Regular(123), SnailfishNumber::pair(Regular(4), Regular(56)))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "[1,2".parse::<SnailfishNumber>(),
+            Err(ParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            "[1;2]".parse::<SnailfishNumber>(),
+            Err(ParseError::UnexpectedCharacter {
+                position: 2,
+                found: ';',
+                expected: "','"
+            })
+        );
+        assert_eq!(
+            "[1,2]]".parse::<SnailfishNumber>(),
+            Err(ParseError::UnexpectedCharacter {
+                position: 5,
+                found: ']',
+                expected: "the end of the number"
+            })
+        );
+        assert_eq!(
+            "[1,99999999999]".parse::<SnailfishNumber>(),
+            Err(ParseError::RegularTooLarge { position: 3 })
+        );
+    }
+
+    // [9,0] inside `depth` pairs that each have a 0 on the right
+    fn nested(depth: usize) -> String {
+        format!("{}[9,0]{}", "[".repeat(depth), ",0]".repeat(depth))
+    }
+
+    #[test]
+    fn nesting_limit() {
+        assert!(nested(MAX_DEPTH - 1).parse::<SnailfishNumber>().is_ok());
+        assert_eq!(
+            nested(MAX_DEPTH).parse::<SnailfishNumber>(),
+            Err(ParseError::TooDeep {
+                position: MAX_DEPTH
+            })
+        );
+        // deep enough to overflow the stack without the limit
+        assert!(matches!(
+            nested(1_000_000).parse::<SnailfishNumber>(),
+            Err(ParseError::TooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn magnitude_overflow() {
+        // every pair triples the 9, and 9 * 3^39 is too big for a u64
+        assert_eq!(parse(&nested(37)).magnitude(), Some(9 * 3u64.pow(38)));
+        assert_eq!(parse(&nested(38)).magnitude(), None);
+    }
+
+    #[test]
+    fn explode() {
+        let examples = [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ];
+
+        for (before, after) in examples {
+            assert_eq!(parse(before).explode(), Ok(Some(parse(after))));
+        }
+        assert_eq!(parse("[[[[0,9],2],3],4]").explode(), Ok(None));
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(parse("[10,11]").split(), Some(parse("[[5,5],11]")));
+        assert_eq!(parse("[1,11]").split(), Some(parse("[1,[5,6]]")));
+        assert_eq!(parse("[1,9]").split(), None);
+    }
+
+    #[test]
+    fn add() {
+        assert_eq!(
+            parse("[[[[4,3],4],4],[7,[[8,4],9]]]") + parse("[1,1]"),
+            parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
+        );
+    }
+
+    #[test]
+    fn trace() {
+        let a = parse("[[[[4,3],4],4],[7,[[8,4],9]]]");
+        let b = parse("[1,1]");
+        let trace: Vec<String> = a
+            .addition_trace(&b)
+            .map(|step| step.unwrap().to_string())
+            .collect();
+
+        assert_eq!(
+            trace,
+            vec![
+                "after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "after split:    [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "after split:    [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "after explode:  [[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+        assert_eq!(
+            a.addition_trace(&b).last().map(|step| step.unwrap().number),
+            Some(&a + &b)
+        );
+        assert_eq!(parse("[1,2]").reduction_steps().next(), None);
+    }
+
+    #[test]
+    fn sum() {
+        let numbers = parse_all("[1,1]\n[2,2]\n[3,3]\n[4,4]\n[5,5]\n[6,6]");
+        assert_eq!(
+            numbers.iter().sum::<SnailfishNumber>(),
+            parse("[[[[5,0],[7,4]],[5,5]],[6,6]]")
+        );
+
+        let sum: SnailfishNumber = parse_all(TEST_INPUT).into_iter().sum();
+        assert_eq!(
+            sum,
+            parse("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]")
+        );
+        assert_eq!(sum.magnitude(), Some(4140));
+    }
+
+    #[test]
+    fn overflow() {
+        // exploding [1,2] adds 1 to the largest u32
+        let a = parse("4294967295");
+        let b = parse("[[[[1,2],3],4],5]");
+
+        assert_eq!(a.checked_add(&b), Err(RegularOverflow));
+        assert_eq!(
+            SnailfishNumber::checked_sum([&a, &b, &b]),
+            Err(RegularOverflow)
+        );
+        assert_eq!(
+            a.addition_trace(&b).collect::<Vec<_>>(),
+            vec![Err(RegularOverflow)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "snailfish addition overflowed")]
+    fn add_panics_on_overflow() {
+        let _ = parse("4294967295") + parse("[[[[1,2],3],4],5]");
+    }
+
+    #[test]
+    fn checked_sum() {
+        let numbers = parse_all(TEST_INPUT);
+
+        assert_eq!(
+            SnailfishNumber::checked_sum(&numbers),
+            Ok(Some(numbers.iter().sum()))
+        );
+        assert_eq!(SnailfishNumber::checked_sum(&[]), Ok(None));
+    }
+
+    #[test]
+    fn magnitude_is_not_destructive() {
+        let number = parse("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]");
+
+        assert_eq!(number.magnitude(), Some(3488));
+        assert_eq!(number.magnitude(), Some(3488));
+        assert_eq!(
+            number.to_string(),
+            "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]"
+        );
+    }
+
+    // Numbers like the homework, where no pair is nested inside four pairs. Regular numbers can
+    // be larger than 9 though, so adding them exercises splits at every depth.
+    fn homework_number() -> impl Strategy<Value = SnailfishNumber> {
+        fn at_depth(depth: usize) -> BoxedStrategy<SnailfishNumber> {
+            let regular = (0..30u32).prop_map(Regular);
+            if depth == EXPLODE_DEPTH {
+                return regular.boxed();
+            }
+            prop_oneof![
+                1 => regular,
+                3 => (at_depth(depth + 1), at_depth(depth + 1))
+                    .prop_map(|(left, right)| SnailfishNumber::pair(left, right)),
+            ]
+            .boxed()
+        }
+
+        (at_depth(1), at_depth(1)).prop_map(|(left, right)| SnailfishNumber::pair(left, right))
+    }
+
+    proptest! {
+        #[test]
+        fn addition_is_reduced(a in homework_number(), b in homework_number()) {
+            prop_assert!((&a + &b).is_reduced());
+        }
+
+        #[test]
+        fn reduction_is_idempotent(a in homework_number(), b in homework_number()) {
+            let sum = &a + &b;
+            prop_assert_eq!(sum.reduce(), Ok(sum));
+        }
+
+        #[test]
+        fn display_round_trip(a in homework_number()) {
+            prop_assert_eq!(parse(&a.to_string()), a);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{RegularOverflow, SnailfishNumber};
+use std::thread;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LargestSum {
+    // indexes into the homework of the left and right number of the sum
+    pub first: usize,
+    pub second: usize,
+    pub magnitude: u64,
+}
+
+impl LargestSum {
+    // the larger magnitude, preferring the earlier pair on a tie so the result doesn't depend on
+    // how the work was split between threads
+    fn max(self, other: LargestSum) -> LargestSum {
+        let key = |sum: &LargestSum| {
+            (
+                sum.magnitude,
+                usize::MAX - sum.first,
+                usize::MAX - sum.second,
+            )
+        };
+        if key(&other) > key(&self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+// The largest magnitude from adding any two different numbers from the homework, in either order.
+// Every first number is handed to one of `threads` threads, each of which tries it with every
+// second number. Returns None if there are fewer than two numbers, or an error if any of the sums
+// overflows.
+pub fn largest_pairwise_sum(
+    numbers: &[SnailfishNumber],
+    threads: usize,
+) -> Result<Option<LargestSum>, RegularOverflow> {
+    let threads = threads.clamp(1, numbers.len().max(1));
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                scope.spawn(move || {
+                    // interleave the first numbers so each thread gets a similar mix of work
+                    let mut largest: Option<LargestSum> = None;
+                    for first in (thread..numbers.len()).step_by(threads) {
+                        for second in (0..numbers.len()).filter(|second| *second != first) {
+                            let sum = LargestSum {
+                                first,
+                                second,
+                                magnitude: numbers[first]
+                                    .checked_add(&numbers[second])?
+                                    .magnitude()
+                                    .expect("reduced numbers are too shallow to overflow"),
+                            };
+                            largest = Some(largest.map_or(sum, |largest| largest.max(sum)));
+                        }
+                    }
+                    Ok(largest)
+                })
+            })
+            .collect();
+
+        let mut largest: Option<LargestSum> = None;
+        for handle in handles {
+            if let Some(sum) = handle.join().expect("a search thread panicked")? {
+                largest = Some(largest.map_or(sum, |largest| largest.max(sum)));
+            }
+        }
+        Ok(largest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "
+        [[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+        [[[5,[2,8]],4],[5,[[9,9],0]]]
+        [6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+        [[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+        [[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+        [[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+        [[[[5,4],[7,7]],8],[[8,3],8]]
+        [[9,3],[[9,9],[6,[4,9]]]]
+        [[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+        [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+    ";
+
+    fn parse_all(input: &str) -> Vec<SnailfishNumber> {
+        input
+            .trim()
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn largest_sum() {
+        let numbers = parse_all(TEST_INPUT);
+        let expected = Some(LargestSum {
+            first: 8,
+            second: 0,
+            magnitude: 3993,
+        });
+
+        for threads in [1, 3, 4, 16] {
+            assert_eq!(largest_pairwise_sum(&numbers, threads), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn ties_prefer_the_earliest_pair() {
+        let numbers = parse_all("[1,1]\n[1,1]\n[1,1]");
+
+        assert_eq!(
+            largest_pairwise_sum(&numbers, 2),
+            Ok(Some(LargestSum {
+                first: 0,
+                second: 1,
+                magnitude: 25
+            }))
+        );
+    }
+
+    #[test]
+    fn too_few_numbers() {
+        assert_eq!(largest_pairwise_sum(&parse_all("[1,2]"), 4), Ok(None));
+        assert_eq!(largest_pairwise_sum(&[], 4), Ok(None));
+    }
+
+    #[test]
+    fn overflow() {
+        // in either order, exploding one of the [1,2] pairs adds to the largest u32
+        let numbers = parse_all("4294967295\n[[[[1,2],0],0],[0,[0,[0,[1,2]]]]]");
+
+        for threads in [1, 2, 3] {
+            assert_eq!(
+                largest_pairwise_sum(&numbers, threads),
+                Err(RegularOverflow)
+            );
+        }
+    }
+}
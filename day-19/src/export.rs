@@ -0,0 +1,242 @@
+// The reconstructed map as files for other tools: a point cloud of the beacons and scanners for a
+// 3D viewer, in PLY or Wavefront OBJ format, and the graph of which scanners were aligned with
+// which as JSON. Each scanner is drawn as a point with a line along each of its x, y and z axes so
+// its rotation can be seen too.
+
+use crate::{beacons, Rotation, Scanner, Vector};
+use std::fmt::Write;
+
+// how long the lines showing each scanner's axes are
+const AXIS_LENGTH: isize = 200;
+
+const BEACON_COLOR: (u8, u8, u8) = (255, 255, 255);
+const SCANNER_COLOR: (u8, u8, u8) = (255, 215, 0);
+const AXIS_COLORS: [(u8, u8, u8); 3] = [(255, 0, 0), (0, 255, 0), (0, 0, 255)];
+
+// the unique beacons in a stable order, so the same map always gives the same file
+fn sorted_beacons(scanners: &[Scanner]) -> Vec<Vector> {
+    let mut beacons: Vec<Vector> = beacons(scanners).into_iter().collect();
+    beacons.sort_unstable();
+    beacons
+}
+
+// the scanners which have been placed, with their positions and rotations
+fn placed(scanners: &[Scanner]) -> impl Iterator<Item = (&Scanner, Vector, Rotation)> {
+    scanners
+        .iter()
+        .filter_map(|scanner| Some((scanner, scanner.position?, scanner.rotation?)))
+}
+
+// the far end of the line along each of the scanner's axes
+fn axis_ends((x, y, z): Vector, rotation: &Rotation) -> [Vector; 3] {
+    let axes = [
+        (AXIS_LENGTH, 0, 0),
+        (0, AXIS_LENGTH, 0),
+        (0, 0, AXIS_LENGTH),
+    ];
+    axes.map(|axis| {
+        let (dx, dy, dz) = rotation.apply(axis);
+        (x + dx, y + dy, z + dz)
+    })
+}
+
+// An ASCII PLY file with a colored vertex for every beacon and scanner and an edge for every
+// scanner axis. The vertices are the beacons, then each scanner followed by the ends of its axes.
+pub fn to_ply(scanners: &[Scanner]) -> String {
+    let beacons = sorted_beacons(scanners);
+    let placed: Vec<_> = placed(scanners).collect();
+    let mut ply = String::new();
+
+    ply.push_str("ply\nformat ascii 1.0\n");
+    for (scanner, _, rotation) in &placed {
+        writeln!(
+            ply,
+            "comment scanner {} rotation {:?}",
+            scanner.number, rotation.0
+        )
+        .unwrap();
+    }
+    writeln!(ply, "element vertex {}", beacons.len() + placed.len() * 4).unwrap();
+    for property in ["float x", "float y", "float z"] {
+        writeln!(ply, "property {}", property).unwrap();
+    }
+    for property in ["uchar red", "uchar green", "uchar blue"] {
+        writeln!(ply, "property {}", property).unwrap();
+    }
+    writeln!(ply, "element edge {}", placed.len() * 3).unwrap();
+    ply.push_str("property int vertex1\nproperty int vertex2\n");
+    ply.push_str("property uchar red\nproperty uchar green\nproperty uchar blue\n");
+    ply.push_str("end_header\n");
+
+    let mut vertex = |(x, y, z): Vector, (r, g, b): (u8, u8, u8)| {
+        writeln!(ply, "{} {} {} {} {} {}", x, y, z, r, g, b).unwrap();
+    };
+    for beacon in &beacons {
+        vertex(*beacon, BEACON_COLOR);
+    }
+    for (_, position, rotation) in &placed {
+        vertex(*position, SCANNER_COLOR);
+        for (end, color) in axis_ends(*position, rotation).iter().zip(AXIS_COLORS) {
+            vertex(*end, color);
+        }
+    }
+
+    for i in 0..placed.len() {
+        let scanner_vertex = beacons.len() + i * 4;
+        for (axis, (r, g, b)) in AXIS_COLORS.iter().enumerate() {
+            writeln!(
+                ply,
+                "{} {} {} {} {}",
+                scanner_vertex,
+                scanner_vertex + axis + 1,
+                r,
+                g,
+                b
+            )
+            .unwrap();
+        }
+    }
+
+    ply
+}
+
+// A Wavefront OBJ file with the beacons as one object of points and each scanner as its own
+// object, with a point for the scanner and a line for each of its axes.
+pub fn to_obj(scanners: &[Scanner]) -> String {
+    let beacons = sorted_beacons(scanners);
+    let mut obj = String::new();
+
+    obj.push_str("o beacons\n");
+    for (x, y, z) in &beacons {
+        writeln!(obj, "v {} {} {}", x, y, z).unwrap();
+    }
+    if !beacons.is_empty() {
+        let indexes: Vec<String> = (1..=beacons.len()).map(|i| i.to_string()).collect();
+        writeln!(obj, "p {}", indexes.join(" ")).unwrap();
+    }
+
+    // OBJ vertex numbers start at 1 and count up through the whole file
+    let mut next_vertex = beacons.len() + 1;
+    for (scanner, position, rotation) in placed(scanners) {
+        writeln!(obj, "o scanner-{}", scanner.number).unwrap();
+        writeln!(obj, "# rotation {:?}", rotation.0).unwrap();
+        for (x, y, z) in [position].iter().chain(&axis_ends(position, &rotation)) {
+            writeln!(obj, "v {} {} {}", x, y, z).unwrap();
+        }
+        writeln!(obj, "p {}", next_vertex).unwrap();
+        for axis in 1..=3 {
+            writeln!(obj, "l {} {}", next_vertex, next_vertex + axis).unwrap();
+        }
+        next_vertex += 4;
+    }
+
+    obj
+}
+
+fn json_vector(vector: Option<Vector>) -> String {
+    vector.map_or("null".to_string(), |(x, y, z)| {
+        format!("[{}, {}, {}]", x, y, z)
+    })
+}
+
+// Every scanner with its position and rotation, which are null if it couldn't be placed, and an
+// edge from each scanner to the scanner it was aligned with.
+pub fn to_json_graph(scanners: &[Scanner]) -> String {
+    let mut json = String::from("{\n  \"scanners\": [\n");
+
+    let nodes: Vec<String> = scanners
+        .iter()
+        .map(|scanner| {
+            let rotation = scanner.rotation.map_or("null".to_string(), |rotation| {
+                let rows: Vec<String> = rotation
+                    .0
+                    .iter()
+                    .map(|[a, b, c]| format!("[{}, {}, {}]", a, b, c))
+                    .collect();
+                format!("[{}]", rows.join(", "))
+            });
+            format!(
+                "    {{\"number\": {}, \"position\": {}, \"rotation\": {}, \"beacons\": {}}}",
+                scanner.number,
+                json_vector(scanner.position),
+                rotation,
+                scanner.beacons.len()
+            )
+        })
+        .collect();
+    json.push_str(&nodes.join(",\n"));
+
+    json.push_str("\n  ],\n  \"alignments\": [\n");
+    let edges: Vec<String> = scanners
+        .iter()
+        .filter_map(|scanner| {
+            scanner
+                .aligned_with
+                .map(|base| format!("    {{\"from\": {}, \"to\": {}}}", base, scanner.number))
+        })
+        .collect();
+    json.push_str(&edges.join(",\n"));
+    json.push_str("\n  ]\n}\n");
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{locate_scanners, parse_scanners, DEFAULT_MIN_OVERLAP};
+
+    const TEST_INPUT: &str = include_str!("../test-input.txt");
+
+    fn located() -> Vec<Scanner> {
+        let mut scanners = parse_scanners(TEST_INPUT);
+        locate_scanners(&mut scanners, DEFAULT_MIN_OVERLAP);
+        scanners
+    }
+
+    #[test]
+    fn ply() {
+        let ply = to_ply(&located());
+        let (header, body) = ply.split_once("end_header\n").unwrap();
+
+        assert!(header.starts_with("ply\nformat ascii 1.0\n"));
+        assert!(header.contains("element vertex 99\n"));
+        assert!(header.contains("element edge 15\n"));
+        assert_eq!(body.lines().count(), 99 + 15);
+        // scanner 1 and the end of its x axis, which is turned to point along -x
+        assert!(body.contains("\n68 -1246 -43 255 215 0\n-132 -1246 -43 255 0 0\n"));
+    }
+
+    #[test]
+    fn obj() {
+        let obj = to_obj(&located());
+
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("v ")).count(),
+            99
+        );
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("l ")).count(),
+            15
+        );
+        assert!(obj.contains("o scanner-1\n# rotation [[-1, 0, 0], [0, 1, 0], [0, 0, -1]]\n"));
+        assert!(obj.contains("v 68 -1246 -43\nv -132 -1246 -43\n"));
+        assert!(obj.contains("p 84\nl 84 85\nl 84 86\nl 84 87\n"));
+    }
+
+    #[test]
+    fn json_graph() {
+        let mut scanners = located();
+        scanners.push(Scanner::new(5, vec![(1, 2, 3)]));
+        let json = to_json_graph(&scanners);
+
+        assert!(json.contains(
+            "{\"number\": 0, \"position\": [0, 0, 0], \"rotation\": [[1, 0, 0], [0, 1, 0], [0, 0, 1]], \"beacons\": 25}"
+        ));
+        assert!(json
+            .contains("{\"number\": 5, \"position\": null, \"rotation\": null, \"beacons\": 1}"));
+        assert!(json.contains("{\"from\": 0, \"to\": 1}"));
+        assert!(json.contains("{\"from\": 1, \"to\": 4}"));
+        assert_eq!(json.matches("\"from\"").count(), 4);
+    }
+}
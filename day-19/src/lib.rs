@@ -0,0 +1,412 @@
+pub mod export;
+
+use itertools::Itertools;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+pub type Vector = (isize, isize, isize);
+
+// A rotation by some multiple of 90 degrees around each axis, as a matrix of 0s, 1s and -1s.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Rotation(pub [[isize; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    // Every way a scanner could be facing. Each row of a rotation picks out a different axis,
+    // possibly negated, which gives 6 * 8 = 48 matrices, but only the half with a determinant of 1
+    // are rotations; the others are reflections.
+    pub fn all() -> Vec<Rotation> {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rotations = vec![];
+
+        for axes in permutations {
+            for signs in 0..8 {
+                let mut matrix = [[0; 3]; 3];
+                for (row, axis) in axes.iter().enumerate() {
+                    matrix[row][*axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+
+                let rotation = Rotation(matrix);
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+
+        rotations
+    }
+
+    pub fn determinant(&self) -> isize {
+        let [[a, b, c], [d, e, f], [g, h, i]] = self.0;
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g)
+    }
+
+    pub fn apply(&self, (x, y, z): Vector) -> Vector {
+        let [a, b, c] = self.0;
+        let row = |[i, j, k]: [isize; 3]| i * x + j * y + k * z;
+        (row(a), row(b), row(c))
+    }
+
+    pub fn transpose(&self) -> Rotation {
+        let m = self.0;
+        Rotation([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+
+    // the rotation which applies `other` and then this one
+    pub fn compose(&self, other: &Rotation) -> Rotation {
+        let mut matrix = [[0; 3]; 3];
+        for (row, cells) in matrix.iter_mut().enumerate() {
+            for (column, cell) in cells.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.0[row][k] * other.0[k][column]).sum();
+            }
+        }
+        Rotation(matrix)
+    }
+}
+
+// how many beacons two scanners need to have in common to be aligned, according to the puzzle
+pub const DEFAULT_MIN_OVERLAP: usize = 12;
+
+fn difference((ax, ay, az): Vector, (bx, by, bz): Vector) -> Vector {
+    (ax - bx, ay - by, az - bz)
+}
+
+fn squared_distance(a: Vector, b: Vector) -> isize {
+    let (dx, dy, dz) = difference(a, b);
+    dx * dx + dy * dy + dz * dz
+}
+
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    pub number: usize,
+    // relative to the scanner until it has been aligned, then relative to scanner 0
+    pub beacons: Vec<Vector>,
+    pub position: Option<Vector>,
+    // how the scanner is turned relative to scanner 0, once it has been aligned
+    pub rotation: Option<Rotation>,
+    // the number of the scanner this one was aligned with
+    pub aligned_with: Option<usize>,
+    // the squared distance between every pair of beacons, sorted, which doesn't change when the
+    // scanner is rotated or moved so it can be compared between scanners before aligning them
+    fingerprint: Vec<isize>,
+}
+
+impl Scanner {
+    pub fn new(number: usize, beacons: Vec<Vector>) -> Self {
+        let mut fingerprint: Vec<isize> = beacons
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| squared_distance(*a, *b))
+            .collect();
+        fingerprint.sort_unstable();
+
+        Scanner {
+            number,
+            beacons,
+            position: None,
+            rotation: None,
+            aligned_with: None,
+            fingerprint,
+        }
+    }
+
+    // the number of beacon distances the two scanners have in common, counting repeats
+    pub fn shared_distances(&self, other: &Scanner) -> usize {
+        let (mut i, mut j, mut shared) = (0, 0, 0);
+
+        while i < self.fingerprint.len() && j < other.fingerprint.len() {
+            match self.fingerprint[i].cmp(&other.fingerprint[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    shared += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        shared
+    }
+
+    // the pairs of beacons (by index) with each squared distance between them
+    fn beacon_pairs(&self) -> HashMap<isize, Vec<(usize, usize)>> {
+        let mut pairs: HashMap<isize, Vec<(usize, usize)>> = HashMap::new();
+
+        for (i, j) in (0..self.beacons.len()).tuple_combinations() {
+            pairs
+                .entry(squared_distance(self.beacons[i], self.beacons[j]))
+                .or_default()
+                .push((i, j));
+        }
+
+        pairs
+    }
+
+    // Finds where the other scanner is relative to this one, returning it with its position set
+    // and its beacons moved into this scanner's frame. Two beacons the same distance apart in
+    // both scanners are probably the same two beacons, so whichever rotation lines up the vectors
+    // between them, and the translation that then lines up the beacons, is tried against all the
+    // other beacons. The scanners need at least `min_overlap` beacons in common, which can't be
+    // fewer than the two it takes to find the rotation.
+    pub fn align(&self, other: &Scanner, min_overlap: usize) -> Option<Scanner> {
+        assert!(
+            min_overlap >= 2,
+            "scanners need at least 2 beacons in common"
+        );
+
+        // every pair of the shared beacons has the same distance between them in both scanners
+        if self.shared_distances(other) < min_overlap * (min_overlap - 1) / 2 {
+            return None;
+        }
+
+        let own_beacons: HashSet<Vector> = self.beacons.iter().copied().collect();
+        let own_pairs = self.beacon_pairs();
+        let rotations = Rotation::all();
+
+        for (distance, other_pairs) in other.beacon_pairs() {
+            let Some(own_pairs) = own_pairs.get(&distance) else {
+                continue;
+            };
+
+            for ((a1, a2), (b1, b2)) in own_pairs.iter().cartesian_product(&other_pairs) {
+                let (a1, a2) = (self.beacons[*a1], self.beacons[*a2]);
+                let own_vector = difference(a2, a1);
+
+                for rotation in &rotations {
+                    // the pair could be the same two beacons either way around
+                    for (b1, b2) in [(*b1, *b2), (*b2, *b1)] {
+                        let (b1, b2) = (
+                            rotation.apply(other.beacons[b1]),
+                            rotation.apply(other.beacons[b2]),
+                        );
+                        if difference(b2, b1) != own_vector {
+                            continue;
+                        }
+
+                        let (dx, dy, dz) = difference(a1, b1);
+                        let beacons: Vec<Vector> = other
+                            .beacons
+                            .iter()
+                            .map(|beacon| {
+                                let (x, y, z) = rotation.apply(*beacon);
+                                (x + dx, y + dy, z + dz)
+                            })
+                            .collect();
+                        let overlap = beacons
+                            .iter()
+                            .filter(|beacon| own_beacons.contains(beacon))
+                            .count();
+
+                        if overlap >= min_overlap {
+                            return Some(Scanner {
+                                number: other.number,
+                                beacons,
+                                position: Some((dx, dy, dz)),
+                                rotation: Some(*rotation),
+                                aligned_with: Some(self.number),
+                                fingerprint: other.fingerprint.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn manhattan_distance(&self, other: &Scanner) -> usize {
+        let (ax, ay, az) = self.position.expect("missing position!");
+        let (bx, by, bz) = other.position.expect("missing position!");
+
+        ((ax - bx).abs() + (ay - by).abs() + (az - bz).abs()) as usize
+    }
+}
+
+pub fn parse_scanners(input: &str) -> Vec<Scanner> {
+    input
+        .trim()
+        .split("\n\n")
+        .map(|scanner_data| {
+            scanner_data
+                .trim()
+                .lines()
+                .skip(1)
+                .map(|coords| {
+                    coords
+                        .trim()
+                        .split(',')
+                        .map(|num| num.parse::<isize>().unwrap())
+                        .collect_tuple()
+                        .unwrap()
+                })
+                .collect()
+        })
+        .enumerate()
+        .map(|(number, beacons)| Scanner::new(number, beacons))
+        .collect()
+}
+
+// Aligns every scanner with scanner 0, setting their positions and moving their beacons to be
+// relative to it. Each newly aligned scanner is used to look for more, so scanners that only
+// overlap with scanner 0 through others are found too. Returns the indices in `scanners` of any
+// which don't overlap enough with the others to be placed, which are left as they were.
+pub fn locate_scanners(scanners: &mut [Scanner], min_overlap: usize) -> Vec<usize> {
+    let Some(first) = scanners.first_mut() else {
+        return vec![];
+    };
+    first.position = Some((0, 0, 0));
+    first.rotation = Some(Rotation::IDENTITY);
+
+    let mut unaligned: Vec<usize> = (1..scanners.len()).collect();
+    let mut scanners_to_check = vec![0];
+
+    while let Some(base) = scanners_to_check.pop() {
+        let mut still_unaligned = vec![];
+
+        for i in unaligned {
+            match scanners[base].align(&scanners[i], min_overlap) {
+                Some(aligned) => {
+                    scanners[i] = aligned;
+                    scanners_to_check.push(i);
+                }
+                None => still_unaligned.push(i),
+            }
+        }
+
+        unaligned = still_unaligned;
+    }
+
+    unaligned.sort_unstable();
+    unaligned
+}
+
+// the beacons seen by the scanners which have been placed
+pub fn beacons(scanners: &[Scanner]) -> HashSet<Vector> {
+    scanners
+        .iter()
+        .filter(|scanner| scanner.position.is_some())
+        .flat_map(|scanner| scanner.beacons.iter().copied())
+        .collect()
+}
+
+pub fn max_manhattan_distance(scanners: &[Scanner]) -> Option<usize> {
+    scanners
+        .iter()
+        .filter(|scanner| scanner.position.is_some())
+        .tuple_combinations()
+        .map(|(a, b)| a.manhattan_distance(b))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = include_str!("../test-input.txt");
+
+    #[test]
+    fn rotations() {
+        let rotations = Rotation::all();
+        let distinct: HashSet<&Rotation> = rotations.iter().collect();
+
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(distinct.len(), 24);
+        assert!(rotations.contains(&Rotation::IDENTITY));
+        for rotation in &rotations {
+            assert_eq!(rotation.determinant(), 1);
+            assert_eq!(rotation.compose(&rotation.transpose()), Rotation::IDENTITY);
+            // composing two rotations gives another one
+            assert!(rotations.contains(&rotation.compose(&rotations[5])));
+        }
+
+        // a quarter turn around z
+        let rotation = Rotation([[0, -1, 0], [1, 0, 0], [0, 0, 1]]);
+        assert_eq!(rotation.apply((1, 2, 3)), (-2, 1, 3));
+    }
+
+    #[test]
+    fn fingerprints() {
+        let scanners = parse_scanners(TEST_INPUT);
+
+        // scanners 0 and 1 share 12 beacons, making 66 distances, but scanners 0 and 2 don't
+        // overlap
+        assert!(scanners[0].shared_distances(&scanners[1]) >= 66);
+        assert!(scanners[0].shared_distances(&scanners[2]) < 66);
+        assert!(scanners[0]
+            .align(&scanners[2], DEFAULT_MIN_OVERLAP)
+            .is_none());
+    }
+
+    #[test]
+    fn align() {
+        let scanners = parse_scanners(TEST_INPUT);
+        let aligned = scanners[0]
+            .align(&scanners[1], DEFAULT_MIN_OVERLAP)
+            .unwrap();
+
+        assert_eq!(aligned.position, Some((68, -1246, -43)));
+        assert!(aligned.beacons.contains(&(-618, -824, -621)));
+        assert!(aligned.beacons.contains(&(459, -707, 401)));
+
+        // they only have 12 beacons in common
+        assert!(scanners[0].align(&scanners[1], 13).is_none());
+    }
+
+    #[test]
+    fn locate() {
+        let mut scanners = parse_scanners(TEST_INPUT);
+        let unplaced = locate_scanners(&mut scanners, DEFAULT_MIN_OVERLAP);
+
+        let positions: Vec<_> = scanners.iter().map(|scanner| scanner.position).collect();
+        assert_eq!(unplaced, vec![]);
+        assert_eq!(
+            positions,
+            vec![
+                Some((0, 0, 0)),
+                Some((68, -1246, -43)),
+                Some((1105, -1205, 1229)),
+                Some((-92, -2380, -20)),
+                Some((-20, -1133, 1061))
+            ]
+        );
+        assert_eq!(beacons(&scanners).len(), 79);
+        assert_eq!(max_manhattan_distance(&scanners), Some(3621));
+    }
+
+    #[test]
+    fn unplaceable_scanners() {
+        let input = format!(
+            "{}\n\n--- scanner 5 ---\n1,2,3\n\n--- scanner 6 ---\n",
+            TEST_INPUT.trim()
+        );
+        let mut scanners = parse_scanners(&input);
+
+        // a lone beacon can't be placed, and neither can a scanner that saw nothing
+        assert_eq!(
+            locate_scanners(&mut scanners, DEFAULT_MIN_OVERLAP),
+            vec![5, 6]
+        );
+        assert_eq!(scanners[5].position, None);
+        assert_eq!(beacons(&scanners).len(), 79);
+        assert_eq!(max_manhattan_distance(&scanners), Some(3621));
+
+        // nothing overlaps by this much, so only scanner 0 is placed
+        let mut scanners = parse_scanners(TEST_INPUT);
+        assert_eq!(locate_scanners(&mut scanners, 13), vec![1, 2, 3, 4]);
+        assert_eq!(max_manhattan_distance(&scanners), None);
+    }
+}
@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod reference;
+
+use std::fmt;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// The finite part of the image as rows of bits, one bit per pixel. Every pixel outside it has the
+// background's value, which is the same everywhere since the whole infinite background is always
+// enhanced the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    words_per_row: usize,
+    // any bits past the width of a row are always 0
+    bits: Vec<u64>,
+    pub background: bool,
+}
+
+impl Image {
+    fn blank(width: usize, height: usize, background: bool) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+
+        Image {
+            width,
+            height,
+            words_per_row,
+            bits: vec![0; words_per_row * height],
+            background,
+        }
+    }
+
+    fn set(&mut self, row: usize, col: usize) {
+        self.bits[row * self.words_per_row + col / WORD_BITS] |= 1 << (col % WORD_BITS);
+    }
+
+    // the pixel at the given position, which can be anywhere in the infinite image
+    pub fn pixel(&self, row: isize, col: isize) -> bool {
+        if row < 0 || col < 0 || row as usize >= self.height || col as usize >= self.width {
+            return self.background;
+        }
+
+        let (row, col) = (row as usize, col as usize);
+        self.bits[row * self.words_per_row + col / WORD_BITS] & (1 << (col % WORD_BITS)) != 0
+    }
+
+    // None if the background is lit, since then infinitely many pixels are
+    pub fn lit_pixels(&self) -> Option<usize> {
+        if self.background {
+            return None;
+        }
+
+        Some(
+            self.bits
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum(),
+        )
+    }
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in 0..self.height as isize {
+            for col in 0..self.width as isize {
+                write!(f, "{}", if self.pixel(row, col) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrenchMap {
+    // whether each 9-bit neighbourhood makes a lit pixel
+    algorithm: [bool; 512],
+    pub image: Image,
+    pub steps_taken: usize,
+}
+
+impl TrenchMap {
+    pub fn new(input: &str) -> Self {
+        let mut lines = input.trim().lines().map(|line| line.trim());
+
+        let algorithm: Vec<bool> = lines
+            .next()
+            .expect("failed to extract the image enhancement algorithm")
+            .chars()
+            .map(|c| c == '#')
+            .collect();
+        let algorithm: [bool; 512] = algorithm
+            .try_into()
+            .expect("the image enhancement algorithm must be 512 characters long");
+
+        let rows: Vec<&str> = lines.filter(|line| !line.is_empty()).collect();
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut image = Image::blank(width, rows.len(), false);
+
+        for (row, pixels) in rows.iter().enumerate() {
+            assert!(pixels.len() == width, "the image must be rectangular");
+            for (col, pixel) in pixels.chars().enumerate() {
+                if pixel == '#' {
+                    image.set(row, col);
+                }
+            }
+        }
+
+        TrenchMap {
+            algorithm,
+            image,
+            steps_taken: 0,
+        }
+    }
+
+    // Enhances the image once, growing it by a pixel on every side since those are the only
+    // pixels outside it that can differ from the background. Each row is scanned left to right
+    // with the 9-bit index kept rolling: shifting it left drops the column that has left the
+    // neighbourhood from each of its three rows, and the new column is shifted in at the bottom.
+    pub fn enhance(&mut self) {
+        let image = &self.image;
+        let mut enhanced = Image::blank(image.width + 2, image.height + 2, false);
+        let background_index = if image.background { 0b111_111_111 } else { 0 };
+
+        for row in 0..enhanced.height {
+            // the rows above, at and below this one in the old image
+            let old_row = row as isize - 1;
+            let mut index = background_index;
+
+            for col in 0..enhanced.width {
+                let old_col = col as isize;
+                index = ((index << 1) & 0b110_110_110)
+                    | (image.pixel(old_row - 1, old_col) as usize) << 6
+                    | (image.pixel(old_row, old_col) as usize) << 3
+                    | image.pixel(old_row + 1, old_col) as usize;
+
+                if self.algorithm[index] {
+                    enhanced.set(row, col);
+                }
+            }
+        }
+
+        enhanced.background = self.algorithm[background_index];
+        self.image = enhanced;
+        self.steps_taken += 1;
+    }
+
+    // enhances the image the given number of times, returning the lit pixels after each step
+    pub fn run(&mut self, steps: usize) -> Vec<Option<usize>> {
+        (0..steps)
+            .map(|_| {
+                self.enhance();
+                self.image.lit_pixels()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reference::InfiniteImage;
+    use super::*;
+
+    const TEST_INPUT: &str = include_str!("../test-input.txt");
+
+    fn reference(input: &str) -> InfiniteImage {
+        let (algorithm, image) = input.trim().split_once("\n\n").unwrap();
+        let algorithm: Vec<char> = algorithm.chars().collect();
+        let image: Vec<Vec<char>> = image.lines().map(|line| line.chars().collect()).collect();
+
+        InfiniteImage::new(&image, &algorithm)
+    }
+
+    #[test]
+    fn parse() {
+        let trench_map = TrenchMap::new(TEST_INPUT);
+
+        assert_eq!(
+            trench_map.image.to_string(),
+            "#..#.\n#....\n##..#\n..#..\n..###\n"
+        );
+        assert_eq!(trench_map.image.lit_pixels(), Some(10));
+    }
+
+    #[test]
+    fn enhance() {
+        let mut trench_map = TrenchMap::new(TEST_INPUT);
+        let counts = trench_map.run(50);
+
+        assert_eq!(counts[1], Some(35));
+        assert_eq!(counts[49], Some(3351));
+        assert_eq!(trench_map.image.width, 105);
+        assert_eq!(trench_map.steps_taken, 50);
+    }
+
+    #[test]
+    fn matches_reference() {
+        let mut trench_map = TrenchMap::new(TEST_INPUT);
+        let mut reference = reference(TEST_INPUT);
+
+        for _ in 0..50 {
+            trench_map.enhance();
+            reference.enhance();
+            assert_eq!(trench_map.image.lit_pixels(), Some(reference.lit_pixels()));
+        }
+    }
+
+    #[test]
+    fn flickering_background() {
+        // lights up every dark neighbourhood, so the background flips between dark and lit
+        let (algorithm, image) = TEST_INPUT.trim().split_once("\n\n").unwrap();
+        let algorithm = format!("#{}.", &algorithm[1..511]);
+        let input = format!("{}\n\n{}", algorithm, image);
+
+        let mut trench_map = TrenchMap::new(&input);
+        let mut reference = reference(&input);
+
+        for step in 1..=20 {
+            trench_map.enhance();
+            reference.enhance();
+
+            if step % 2 == 1 {
+                assert_eq!(trench_map.image.lit_pixels(), None);
+            } else {
+                assert_eq!(trench_map.image.lit_pixels(), Some(reference.lit_pixels()));
+            }
+        }
+    }
+
+    #[test]
+    fn hundreds_of_steps() {
+        let mut trench_map = TrenchMap::new(TEST_INPUT);
+        let counts = trench_map.run(300);
+
+        assert_eq!(counts.len(), 300);
+        assert_eq!(trench_map.image.height, 5 + 2 * 300);
+        assert!(counts.iter().all(|count| count.is_some()));
+    }
+}
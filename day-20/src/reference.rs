@@ -0,0 +1,96 @@
+// The original HashSet implementation, kept to check the bitset one against. Pixels outside the
+// tracked area take the background's value, but only the tracked area is counted.
+
+use std::collections::HashSet;
+
+type Image = HashSet<(isize, isize)>;
+
+pub struct InfiniteImage {
+    top: isize,
+    bottom: isize,
+    left: isize,
+    right: isize,
+    image: Image,
+    background: char,
+    algorithm: Vec<char>,
+}
+
+impl InfiniteImage {
+    pub fn new(image_vec: &[Vec<char>], algorithm: &[char]) -> Self {
+        let mut image: Image = HashSet::new();
+
+        let top = 0;
+        let bottom = (image_vec.len() - 1) as isize;
+        let left = 0;
+        let right = (image_vec[0].len() - 1) as isize;
+
+        for row in top..=bottom {
+            for col in left..=right {
+                if image_vec[row as usize][col as usize] == '#' {
+                    image.insert((row, col));
+                }
+            }
+        }
+
+        InfiniteImage {
+            top,
+            bottom,
+            left,
+            right,
+            image,
+            background: '.',
+            algorithm: algorithm.to_vec(),
+        }
+    }
+
+    fn algorithm_index_for(&self, (x, y): (isize, isize)) -> usize {
+        let mut binary_string = String::with_capacity(9);
+        for row in (x - 1)..=(x + 1) {
+            for col in (y - 1)..=(y + 1) {
+                // handle background case
+                let digit = if (self.top..=self.bottom).contains(&row)
+                    && (self.left..=self.right).contains(&col)
+                {
+                    match self.image.contains(&(row, col)) {
+                        true => "1",
+                        false => "0",
+                    }
+                } else {
+                    match self.background {
+                        '#' => "1",
+                        _ => "0",
+                    }
+                };
+                binary_string.push_str(digit);
+            }
+        }
+        usize::from_str_radix(&binary_string, 2).expect("programming error")
+    }
+
+    fn expand(&mut self) {
+        self.top -= 1;
+        self.left -= 1;
+        self.bottom += 1;
+        self.right += 1;
+    }
+
+    pub fn enhance(&mut self) {
+        let mut new_image: Image = HashSet::new();
+
+        for row in (self.top - 1)..=(self.bottom + 1) {
+            for col in (self.left - 1)..=(self.right + 1) {
+                if self.algorithm[self.algorithm_index_for((row, col))] == '#' {
+                    new_image.insert((row, col));
+                }
+            }
+        }
+
+        self.expand();
+        self.image = new_image;
+        self.background = self.algorithm[self.algorithm_index_for((self.top - 2, self.left - 2))];
+    }
+
+    pub fn lit_pixels(&self) -> usize {
+        self.image.len()
+    }
+}